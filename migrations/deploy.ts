@@ -3,10 +3,143 @@
 // configured from the workspace's Anchor.toml.
 
 const anchor = require("@project-serum/anchor");
+const fs = require("fs");
+
+// the singleton accounts stake and lock_stake require, created once with
+// settings that keep the staking flow as it was before they existed
+const PARTY_RULES = { minPartySize: 1, maxPartySize: 10, constraints: [] };
+const DESTINATION_ID = 0;
+const DESTINATION_CONFIG = {
+  name: "",
+  allowedDurations: [],
+  entryFee: new anchor.BN(0),
+  partyRules: PARTY_RULES,
+  lootTableId: 0,
+  capacity: 0,
+};
+
+async function findPda(program, seeds) {
+  return anchor.web3.PublicKey.findProgramAddress(
+    seeds.map((seed) => Buffer.from(anchor.utils.bytes.utf8.encode(seed))),
+    program.programId
+  );
+}
+
+async function createIfMissing(provider, pubkey, create) {
+  if ((await provider.connection.getAccountInfo(pubkey)) === null) {
+    await create();
+  }
+}
 
 module.exports = async function (provider) {
   // Configure client to use the provider.
   anchor.setProvider(provider);
 
-  // Add your deploy script here.
-}
+  const idl = JSON.parse(
+    fs.readFileSync("target/idl/nft_staking.json").toString()
+  );
+  const program = new anchor.Program(idl, idl.metadata.address, provider);
+
+  const admin = provider.wallet.publicKey;
+  const systemProgram = anchor.web3.SystemProgram.programId;
+  const rent = anchor.web3.SYSVAR_RENT_PUBKEY;
+
+  const [stakingPubkey, stakingBump] = await findPda(program, ["nft_staking"]);
+  if ((await provider.connection.getAccountInfo(stakingPubkey)) === null) {
+    // nothing to migrate before initialize
+    return;
+  }
+
+  // grow the staking account to the current layout
+  await program.rpc.migrateStaking(stakingBump, {
+    accounts: {
+      admin,
+      stakingAccount: stakingPubkey,
+      systemProgram,
+    },
+  });
+
+  // no eligibility rules, the authorized creators and name starts apply
+  const [eligibilityRulesPubkey, eligibilityRulesBump] = await findPda(program, [
+    "eligibility_rules",
+  ]);
+  await createIfMissing(provider, eligibilityRulesPubkey, () =>
+    program.rpc.setEligibilityRules(stakingBump, eligibilityRulesBump, [], {
+      accounts: {
+        stakingAccount: stakingPubkey,
+        eligibilityRulesAccount: eligibilityRulesPubkey,
+        admin,
+        systemProgram,
+        rent,
+      },
+    })
+  );
+
+  const [mintBlocklistPubkey, mintBlocklistBump] = await findPda(program, [
+    "mint_blocklist",
+  ]);
+  await createIfMissing(provider, mintBlocklistPubkey, () =>
+    program.rpc.addBlockedMints(stakingBump, mintBlocklistBump, [], {
+      accounts: {
+        stakingAccount: stakingPubkey,
+        mintBlocklistAccount: mintBlocklistPubkey,
+        admin,
+        systemProgram,
+        rent,
+      },
+    })
+  );
+
+  const [partyRulesPubkey, partyRulesBump] = await findPda(program, [
+    "party_rules",
+  ]);
+  await createIfMissing(provider, partyRulesPubkey, () =>
+    program.rpc.setPartyRules(stakingBump, partyRulesBump, PARTY_RULES, {
+      accounts: {
+        stakingAccount: stakingPubkey,
+        partyRulesAccount: partyRulesPubkey,
+        admin,
+        systemProgram,
+        rent,
+      },
+    })
+  );
+
+  const [synergiesPubkey, synergiesBump] = await findPda(program, [
+    "synergies",
+  ]);
+  await createIfMissing(provider, synergiesPubkey, () =>
+    program.rpc.setSynergies(stakingBump, synergiesBump, [], {
+      accounts: {
+        stakingAccount: stakingPubkey,
+        synergiesAccount: synergiesPubkey,
+        admin,
+        systemProgram,
+        rent,
+      },
+    })
+  );
+
+  // the default destination, open to any duration the staking period allows
+  const [destinationPubkey, destinationBump] = await findPda(program, [
+    "destination",
+    DESTINATION_ID.toString(),
+  ]);
+  await createIfMissing(provider, destinationPubkey, () =>
+    program.rpc.setDestination(
+      stakingBump,
+      DESTINATION_ID,
+      destinationBump,
+      DESTINATION_CONFIG,
+      {
+        accounts: {
+          stakingAccount: stakingPubkey,
+          destinationAccount: destinationPubkey,
+          admin,
+          systemProgram,
+          rent,
+        },
+      }
+    )
+  );
+};
@@ -2,8 +2,7 @@ pub mod utils;
 
 use crate::utils::*;
 use anchor_lang::{prelude::*, Discriminator};
-use anchor_spl::token::{Mint, Token, TokenAccount,};
-use spl_token::{instruction::AuthorityType, state::AccountState};
+use spl_token_2022::{instruction::AuthorityType, state::AccountState};
 use arrayref::array_ref;
 
 #[cfg(all(not(feature = "local-testing"), not(feature = "aurorynet")))]
@@ -17,20 +16,196 @@ declare_id!("FAoe56PmccajBhQ8h5F7vp4RMEY3D3xogqXXBDb83MHF");
 pub mod constants {
     pub const AURY_TOKEN_MINT_PUBKEY: &str = "AURYydfxJib1ZkTir1Jn1J9ECYUtjb6rKQVmtYaixWPP";
     pub const STAKING_PDA_SEED: &[u8] = b"nft_staking";
+    pub const AURORIAN_REGISTRY_PDA_SEED: &[u8] = b"aurorian_registry";
+    pub const ELIGIBILITY_RULES_PDA_SEED: &[u8] = b"eligibility_rules";
+    pub const MINT_BLOCKLIST_PDA_SEED: &[u8] = b"mint_blocklist";
+    pub const REWARD_CONFIG_PDA_SEED: &[u8] = b"reward_config";
+    pub const RECIPE_PDA_SEED: &[u8] = b"recipe";
+    pub const AURORIAN_STATS_PDA_SEED: &[u8] = b"aurorian_stats";
+    pub const STAKE_RECORD_PDA_SEED: &[u8] = b"stake_record";
+    pub const PARTY_RULES_PDA_SEED: &[u8] = b"party_rules";
+    pub const SYNERGIES_PDA_SEED: &[u8] = b"synergies";
+    pub const DESTINATION_PDA_SEED: &[u8] = b"destination";
+    pub const ROUND_PDA_SEED: &[u8] = b"round";
+    pub const WALLET_POSITIONS_PDA_SEED: &[u8] = b"wallet_positions";
 }
 
 #[cfg(feature = "local-testing")]
 pub mod constants {
     pub const AURY_TOKEN_MINT_PUBKEY: &str = "teST1ieLrLdr4MJPZ7i8mgSCLQ7rTrPRjNnyFdHFaz9";
     pub const STAKING_PDA_SEED: &[u8] = b"nft_staking";
+    pub const AURORIAN_REGISTRY_PDA_SEED: &[u8] = b"aurorian_registry";
+    pub const ELIGIBILITY_RULES_PDA_SEED: &[u8] = b"eligibility_rules";
+    pub const MINT_BLOCKLIST_PDA_SEED: &[u8] = b"mint_blocklist";
+    pub const REWARD_CONFIG_PDA_SEED: &[u8] = b"reward_config";
+    pub const RECIPE_PDA_SEED: &[u8] = b"recipe";
+    pub const AURORIAN_STATS_PDA_SEED: &[u8] = b"aurorian_stats";
+    pub const STAKE_RECORD_PDA_SEED: &[u8] = b"stake_record";
+    pub const PARTY_RULES_PDA_SEED: &[u8] = b"party_rules";
+    pub const SYNERGIES_PDA_SEED: &[u8] = b"synergies";
+    pub const DESTINATION_PDA_SEED: &[u8] = b"destination";
+    pub const ROUND_PDA_SEED: &[u8] = b"round";
+    pub const WALLET_POSITIONS_PDA_SEED: &[u8] = b"wallet_positions";
 }
 
 #[cfg(feature = "aurorynet")]
 pub mod constants {
     pub const AURY_TOKEN_MINT_PUBKEY: &str = "FAurynpWGGxrqmjiFUbCokq27QoJLF7u32UGhL2FQB78";
     pub const STAKING_PDA_SEED: &[u8] = b"nft_staking";
+    pub const AURORIAN_REGISTRY_PDA_SEED: &[u8] = b"aurorian_registry";
+    pub const ELIGIBILITY_RULES_PDA_SEED: &[u8] = b"eligibility_rules";
+    pub const MINT_BLOCKLIST_PDA_SEED: &[u8] = b"mint_blocklist";
+    pub const REWARD_CONFIG_PDA_SEED: &[u8] = b"reward_config";
+    pub const RECIPE_PDA_SEED: &[u8] = b"recipe";
+    pub const AURORIAN_STATS_PDA_SEED: &[u8] = b"aurorian_stats";
+    pub const STAKE_RECORD_PDA_SEED: &[u8] = b"stake_record";
+    pub const PARTY_RULES_PDA_SEED: &[u8] = b"party_rules";
+    pub const SYNERGIES_PDA_SEED: &[u8] = b"synergies";
+    pub const DESTINATION_PDA_SEED: &[u8] = b"destination";
+    pub const ROUND_PDA_SEED: &[u8] = b"round";
+    pub const WALLET_POSITIONS_PDA_SEED: &[u8] = b"wallet_positions";
 }
 
+pub const MAX_AURY_EMISSION_RATES: usize = 5;
+pub const MAX_RARITY_MULTIPLIERS: usize = 10;
+pub const MAX_AUTHORIZED_COLLECTIONS: usize = 5;
+pub const MAX_AUTHORIZED_CREATORS: usize = 5;
+pub const MAX_BLOCKED_MINTS: usize = 250;
+pub const MAX_STAKED_ITEMS: usize = 5;
+pub const MAX_EQUIPMENT: usize = 4;
+pub const MAX_RECIPE_INPUTS: usize = 5;
+pub const MAX_ELIGIBILITY_RULES: usize = 16;
+pub const MAX_PARTY_CONSTRAINTS: usize = 8;
+pub const MAX_SYNERGIES: usize = 8;
+pub const MAX_ALLOWED_DURATIONS: usize = 5;
+pub const MAX_NAME_LENGTH: usize = 32;
+pub const BASIS_POINTS: u64 = 10_000;
+// locked seconds per xp point of an unweighted aurorian
+pub const XP_PERIOD: u64 = 3600;
+pub const XP_PER_LEVEL: u64 = 100;
+// 8: account's signature on the anchor
+// 32: admin_key
+// 1: freeze_program
+// 32: authorized_creator
+// 4: authorized_name_starts Vec's length
+// 32 * 150: authorized_name_starts limit 150 and max_length 32
+// 8: minimum_staking_period
+// 8: maximum_staking_period
+// 4: active_rewards Vec's length
+// 32 * 150: active_rewards limit 150
+// 4: aury_emission_rates Vec's length
+// (8 + 8) * 5: aury_emission_rates limit 5
+// 8: aury_emission_pool
+// 1: minimum_rarity
+// 4: rarity_multipliers Vec's length
+// 2 * 10: rarity_multipliers limit 10
+// 4: authorized_collections Vec's length
+// 32 * 5: authorized_collections limit 5
+// 4: authorized_creators Vec's length
+// 32 * 5: authorized_creators limit 5
+// 8: expedition_cooldown
+// 4: active_positions
+// 4: active_nfts
+// 4: max_active_positions
+// 4: max_active_nfts
+// 4: max_wallet_positions
+pub const STAKING_SPACE: usize = 8
+    + 32
+    + 1
+    + 32
+    + 4
+    + 32 * 150
+    + 8
+    + 8
+    + 4
+    + 32 * 150
+    + 4
+    + (8 + 8) * 5
+    + 8
+    + 1
+    + 4
+    + 2 * 10
+    + 4
+    + 32 * 5
+    + 4
+    + 32 * 5
+    + 8
+    + 4
+    + 4
+    + 4
+    + 4
+    + 4;
+// positions locked before the terms were captured read version 0
+pub const POSITION_CONFIG_VERSION: u8 = 1;
+// 8 + 32 + 4 + 8 + 8 + 2 + 8
+pub const AURORIAN_STATS_SPACE: usize = 70;
+// 8 + 32 + 32 + 4 + 32 + 8
+pub const STAKE_RECORD_SPACE: usize = 116;
+// 8 + 32 + 4
+pub const WALLET_POSITIONS_SPACE: usize = 44;
+// 8: account's signature on the anchor
+// 4: index
+// 32: wallet
+// 4: nft_mint_keys Vec's length
+// 32 * 10: nft_mint_keys limit 10
+// 4: claimable Vec's length
+// (32 + 2) * 5: claimable limit 5
+// 8: staking_at
+// 8: staking_period
+// 8: claimable aury amount
+// 8: aury_deposit
+// 8: aury_emission_rate
+// 8: last_aury_accrual_at
+// 4: staked_nfts Vec's length
+// (32 + 1 + 4 + 1 + 4 + 32 + 4 + 10 + 32 + 1 + 32) * 10: staked_nfts limit 10, name max_length 32 and symbol max_length 10
+// 4: staked_items Vec's length
+// (32 + 8) * 5: staked_items limit 5
+// 4: equipment Vec's length
+// (32 + 1) * 4: equipment limit 4
+// 8: aury_boost
+// 8: odds_boost
+// 8: synergy_multiplier
+// 4: destination_id
+// 1 + 4: round_id
+// 1 + 8 + 8 + 4 + 8 * 5 + 4 + 2 * 10 + 4 + (8 + 8) * 5 + 8 + 8 + 4: config
+pub const USER_STAKING_SPACE: usize = 8
+    + 4
+    + 32
+    + 4
+    + 32 * 10
+    + 4
+    + (32 + 2) * 5
+    + 8
+    + 8
+    + 8
+    + 8
+    + 8
+    + 8
+    + 4
+    + (32 + 1 + 4 + 1 + 4 + 32 + 4 + 10 + 32 + 1 + 32) * 10
+    + 4
+    + (32 + 8) * 5
+    + 4
+    + (32 + 1) * 4
+    + 8
+    + 8
+    + 8
+    + 4
+    + 1
+    + 4
+    + 1
+    + 8
+    + 8
+    + 4
+    + 8 * 5
+    + 4
+    + 2 * 10
+    + 4
+    + (8 + 8) * 5
+    + 8
+    + 8
+    + 4;
+
 #[program]
 pub mod nft_staking {
     use super::*;
@@ -38,7 +213,7 @@ pub mod nft_staking {
     pub fn initialize(
         ctx: Context<Initialize>,
         _nonce_staking: u8,
-        _nonce_aury_vault: u8,
+        nonce_aury_vault: u8,
         authorized_creator: Pubkey,
         authorized_name_starts: Vec<String>,
         minimum_staking_period: u64,
@@ -57,6 +232,23 @@ pub mod nft_staking {
         ctx.accounts.staking_account.minimum_staking_period = minimum_staking_period;
         ctx.accounts.staking_account.maximum_staking_period = maximum_staking_period;
 
+        // compute aury vault account signer seeds
+        let aury_mint_key = ctx.accounts.aury_mint.key();
+        let aury_vault_account_seeds = &[aury_mint_key.as_ref(), &[nonce_aury_vault]];
+        let aury_vault_account_signer = &aury_vault_account_seeds[..];
+
+        // initialize aury vault account, owned by itself
+        spl_init_token_account(InitializeTokenAccountParams {
+            account: ctx.accounts.aury_vault.to_account_info(),
+            account_signer_seeds: aury_vault_account_signer,
+            mint: ctx.accounts.aury_mint.to_account_info(),
+            owner: ctx.accounts.aury_vault.to_account_info(),
+            payer: ctx.accounts.initializer.to_account_info(),
+            system_program: ctx.accounts.system_program.to_account_info(),
+            token_program: ctx.accounts.token_program.to_account_info(),
+            rent: ctx.accounts.rent.to_account_info(),
+        })?;
+
         Ok(())
     }
 
@@ -89,6 +281,47 @@ pub mod nft_staking {
         Ok(())
     }
 
+    #[access_control(is_admin(&ctx.accounts.staking_account, &ctx.accounts.admin))]
+    pub fn add_authorized_creators(
+        ctx: Context<AddAuthorizedCreators>,
+        _nonce_staking: u8,
+        new_authorized_creators: Vec<Pubkey>,
+    ) -> ProgramResult {
+        for new_authorized_creator in new_authorized_creators.iter() {
+            if !ctx
+                .accounts
+                .staking_account
+                .authorized_creators
+                .contains(new_authorized_creator)
+            {
+                ctx.accounts
+                    .staking_account
+                    .authorized_creators
+                    .push(*new_authorized_creator);
+            }
+        }
+
+        if ctx.accounts.staking_account.authorized_creators.len() > MAX_AUTHORIZED_CREATORS {
+            return Err(ErrorCode::TooManyAuthorizedCreators.into());
+        }
+
+        Ok(())
+    }
+
+    #[access_control(is_admin(&ctx.accounts.staking_account, &ctx.accounts.admin))]
+    pub fn remove_authorized_creators(
+        ctx: Context<RemoveAuthorizedCreators>,
+        _nonce_staking: u8,
+        old_authorized_creators: Vec<Pubkey>,
+    ) -> ProgramResult {
+        ctx.accounts
+            .staking_account
+            .authorized_creators
+            .retain(|authorized_creator| !old_authorized_creators.contains(authorized_creator));
+
+        Ok(())
+    }
+
     #[access_control(is_admin(&ctx.accounts.staking_account, &ctx.accounts.admin))]
     pub fn update_staking_period(
         ctx: Context<UpdateStakingPeriod>,
@@ -108,6 +341,117 @@ pub mod nft_staking {
         Ok(())
     }
 
+    #[access_control(is_admin(&ctx.accounts.staking_account, &ctx.accounts.admin))]
+    pub fn update_expedition_cooldown(
+        ctx: Context<UpdateExpeditionCooldown>,
+        _nonce_staking: u8,
+        new_expedition_cooldown: u64,
+    ) -> ProgramResult {
+        ctx.accounts.staking_account.expedition_cooldown = new_expedition_cooldown;
+
+        Ok(())
+    }
+
+    #[access_control(is_admin(&ctx.accounts.staking_account, &ctx.accounts.admin))]
+    pub fn update_capacity(
+        ctx: Context<UpdateCapacity>,
+        _nonce_staking: u8,
+        new_max_active_positions: u32,
+        new_max_active_nfts: u32,
+        new_max_wallet_positions: u32,
+    ) -> ProgramResult {
+        ctx.accounts.staking_account.max_active_positions = new_max_active_positions;
+        ctx.accounts.staking_account.max_active_nfts = new_max_active_nfts;
+        ctx.accounts.staking_account.max_wallet_positions = new_max_wallet_positions;
+
+        Ok(())
+    }
+
+    #[access_control(is_admin(&ctx.accounts.staking_account, &ctx.accounts.admin))]
+    pub fn update_aury_emission_rates(
+        ctx: Context<UpdateAuryEmissionRates>,
+        _nonce_staking: u8,
+        new_aury_emission_rates: Vec<AuryEmissionRate>,
+    ) -> ProgramResult {
+        if new_aury_emission_rates.len() > MAX_AURY_EMISSION_RATES {
+            return Err(ErrorCode::InvalidAuryEmissionRates.into());
+        }
+
+        ctx.accounts.staking_account.aury_emission_rates = new_aury_emission_rates;
+
+        Ok(())
+    }
+
+    #[access_control(is_admin(&ctx.accounts.staking_account, &ctx.accounts.admin))]
+    pub fn fund_aury_emission(
+        ctx: Context<FundAuryEmission>,
+        _nonce_staking: u8,
+        _nonce_aury_vault: u8,
+        aury_amount: u64,
+    ) -> ProgramResult {
+        // transfer aury to the vault
+        spl_token_transfer(TokenTransferParams {
+            source: ctx.accounts.aury_from.to_account_info(),
+            destination: ctx.accounts.aury_vault.to_account_info(),
+            mint: ctx.accounts.aury_mint.to_account_info(),
+            amount: aury_amount,
+            authority: ctx.accounts.admin.to_account_info(),
+            authority_signer_seeds: &[],
+            token_program: ctx.accounts.token_program.to_account_info(),
+        })?;
+
+        // update emission pool
+        ctx.accounts.staking_account.aury_emission_pool = ctx
+            .accounts
+            .staking_account
+            .aury_emission_pool
+            .checked_add(aury_amount)
+            .ok_or(ErrorCode::NumericalOverflow)?;
+
+        Ok(())
+    }
+
+    #[access_control(is_admin(&ctx.accounts.staking_account, &ctx.accounts.admin))]
+    pub fn update_rarity_config(
+        ctx: Context<UpdateRarityConfig>,
+        _nonce_staking: u8,
+        new_minimum_rarity: u8,
+        new_rarity_multipliers: Vec<u16>,
+    ) -> ProgramResult {
+        if new_rarity_multipliers.len() > MAX_RARITY_MULTIPLIERS {
+            return Err(ErrorCode::InvalidRarityMultipliers.into());
+        }
+
+        ctx.accounts.staking_account.minimum_rarity = new_minimum_rarity;
+        ctx.accounts.staking_account.rarity_multipliers = new_rarity_multipliers;
+
+        Ok(())
+    }
+
+    #[access_control(is_admin(&ctx.accounts.staking_account, &ctx.accounts.admin))]
+    pub fn set_aurorian_registry(
+        ctx: Context<SetAurorianRegistry>,
+        _nonce_staking: u8,
+        _nonce_aurorian_registry: u8,
+        rarity: u8,
+        traits: u32,
+    ) -> ProgramResult {
+        ctx.accounts.aurorian_registry_account.nft_mint = ctx.accounts.nft_mint.key();
+        ctx.accounts.aurorian_registry_account.rarity = rarity;
+        ctx.accounts.aurorian_registry_account.traits = traits;
+
+        Ok(())
+    }
+
+    #[access_control(is_admin(&ctx.accounts.staking_account, &ctx.accounts.admin))]
+    pub fn remove_aurorian_registry(
+        ctx: Context<RemoveAurorianRegistry>,
+        _nonce_staking: u8,
+        _nonce_aurorian_registry: u8,
+    ) -> ProgramResult {
+        Ok(())
+    }
+
     #[access_control(is_admin(&ctx.accounts.staking_account, &ctx.accounts.admin))]
     pub fn add_authorized_name_starts(
         ctx: Context<UpdateAuthorizedCreator>,
@@ -161,6 +505,136 @@ pub mod nft_staking {
         Ok(())
     }
 
+    #[access_control(is_admin(&ctx.accounts.staking_account, &ctx.accounts.admin))]
+    pub fn add_authorized_collections(
+        ctx: Context<AddAuthorizedCollections>,
+        _nonce_staking: u8,
+        new_authorized_collections: Vec<Pubkey>,
+    ) -> ProgramResult {
+        for new_authorized_collection in new_authorized_collections.iter() {
+            if !ctx
+                .accounts
+                .staking_account
+                .authorized_collections
+                .contains(new_authorized_collection)
+            {
+                ctx.accounts
+                    .staking_account
+                    .authorized_collections
+                    .push(*new_authorized_collection);
+            }
+        }
+
+        if ctx.accounts.staking_account.authorized_collections.len() > MAX_AUTHORIZED_COLLECTIONS {
+            return Err(ErrorCode::TooManyAuthorizedCollections.into());
+        }
+
+        Ok(())
+    }
+
+    #[access_control(is_admin(&ctx.accounts.staking_account, &ctx.accounts.admin))]
+    pub fn remove_authorized_collections(
+        ctx: Context<RemoveAuthorizedCollections>,
+        _nonce_staking: u8,
+        old_authorized_collections: Vec<Pubkey>,
+    ) -> ProgramResult {
+        ctx.accounts
+            .staking_account
+            .authorized_collections
+            .retain(|authorized_collection| !old_authorized_collections.contains(authorized_collection));
+
+        Ok(())
+    }
+
+    #[access_control(is_admin(&ctx.accounts.staking_account, &ctx.accounts.admin))]
+    pub fn set_eligibility_rules(
+        ctx: Context<SetEligibilityRules>,
+        _nonce_staking: u8,
+        _nonce_eligibility_rules: u8,
+        rules: Vec<EligibilityRule>,
+    ) -> ProgramResult {
+        assert_eligibility_rules_valid(&rules)?;
+
+        ctx.accounts.eligibility_rules_account.rules = rules;
+
+        Ok(())
+    }
+
+    #[access_control(is_admin(&ctx.accounts.staking_account, &ctx.accounts.admin))]
+    pub fn set_party_rules(
+        ctx: Context<SetPartyRules>,
+        _nonce_staking: u8,
+        _nonce_party_rules: u8,
+        party_rules: PartyRules,
+    ) -> ProgramResult {
+        assert_party_rules_valid(&party_rules)?;
+
+        ctx.accounts.party_rules_account.party_rules = party_rules;
+
+        Ok(())
+    }
+
+    #[access_control(is_admin(&ctx.accounts.staking_account, &ctx.accounts.admin))]
+    pub fn set_synergies(
+        ctx: Context<SetSynergies>,
+        _nonce_staking: u8,
+        _nonce_synergies: u8,
+        synergies: Vec<Synergy>,
+    ) -> ProgramResult {
+        assert_synergies_valid(&synergies)?;
+
+        ctx.accounts.synergies_account.synergies = synergies;
+
+        Ok(())
+    }
+
+    #[access_control(is_admin(&ctx.accounts.staking_account, &ctx.accounts.admin))]
+    pub fn add_blocked_mints(
+        ctx: Context<AddBlockedMints>,
+        _nonce_staking: u8,
+        _nonce_mint_blocklist: u8,
+        new_blocked_mints: Vec<BlockedMint>,
+    ) -> ProgramResult {
+        let mint_blocklist_account = &mut ctx.accounts.mint_blocklist_account;
+
+        for new_blocked_mint in new_blocked_mints.iter() {
+            match mint_blocklist_account
+                .blocked_mints
+                .iter()
+                .position(|blocked_mint| blocked_mint.nft_mint == new_blocked_mint.nft_mint)
+            {
+                Some(index) => {
+                    // update the reason of the blocked mint
+                    mint_blocklist_account.blocked_mints[index].reason = new_blocked_mint.reason;
+                }
+                None => {
+                    mint_blocklist_account.blocked_mints.push(*new_blocked_mint);
+                }
+            }
+        }
+
+        if mint_blocklist_account.blocked_mints.len() > MAX_BLOCKED_MINTS {
+            return Err(ErrorCode::TooManyBlockedMints.into());
+        }
+
+        Ok(())
+    }
+
+    #[access_control(is_admin(&ctx.accounts.staking_account, &ctx.accounts.admin))]
+    pub fn remove_blocked_mints(
+        ctx: Context<RemoveBlockedMints>,
+        _nonce_staking: u8,
+        _nonce_mint_blocklist: u8,
+        old_nft_mints: Vec<Pubkey>,
+    ) -> ProgramResult {
+        ctx.accounts
+            .mint_blocklist_account
+            .blocked_mints
+            .retain(|blocked_mint| !old_nft_mints.contains(&blocked_mint.nft_mint));
+
+        Ok(())
+    }
+
     #[access_control(is_admin(&ctx.accounts.staking_account, &ctx.accounts.admin))]
     pub fn add_reward(
         ctx: Context<AddReward>,
@@ -187,6 +661,148 @@ pub mod nft_staking {
         Ok(())
     }
 
+    #[access_control(is_admin(&ctx.accounts.staking_account, &ctx.accounts.admin))]
+    pub fn set_reward_config(
+        ctx: Context<SetRewardConfig>,
+        _nonce_staking: u8,
+        _nonce_reward_config: u8,
+        equipment: EquipmentConfig,
+        consumable: ConsumableConfig,
+    ) -> ProgramResult {
+        // Check if nft is one of the rewards
+        if !ctx
+            .accounts
+            .staking_account
+            .active_rewards
+            .contains(ctx.accounts.nft_mint.key)
+        {
+            return Err(ErrorCode::InvalidMintForReward.into());
+        }
+
+        ctx.accounts.reward_config_account.nft_mint = *ctx.accounts.nft_mint.key;
+        ctx.accounts.reward_config_account.equipment = equipment;
+        ctx.accounts.reward_config_account.consumable = consumable;
+
+        Ok(())
+    }
+
+    #[access_control(is_admin(&ctx.accounts.staking_account, &ctx.accounts.admin))]
+    pub fn set_recipe(
+        ctx: Context<SetRecipe>,
+        _nonce_staking: u8,
+        recipe_id: u32,
+        _nonce_recipe: u8,
+        inputs: Vec<RecipeInput>,
+        output_amount: u64,
+        output_supply_cap: u64,
+    ) -> ProgramResult {
+        assert_recipe_valid(&inputs, output_amount)?;
+
+        // Check if the output is one of the rewards
+        if !ctx
+            .accounts
+            .staking_account
+            .active_rewards
+            .contains(ctx.accounts.output_mint.key)
+        {
+            return Err(ErrorCode::InvalidMintForReward.into());
+        }
+
+        let recipe_account = &mut ctx.accounts.recipe_account;
+        recipe_account.recipe_id = recipe_id;
+        recipe_account.inputs = inputs;
+        recipe_account.output_mint = *ctx.accounts.output_mint.key;
+        recipe_account.output_amount = output_amount;
+        recipe_account.output_supply_cap = output_supply_cap;
+
+        Ok(())
+    }
+
+    #[access_control(is_admin(&ctx.accounts.staking_account, &ctx.accounts.admin))]
+    pub fn remove_recipe(
+        ctx: Context<RemoveRecipe>,
+        _nonce_staking: u8,
+        _recipe_id: u32,
+        _nonce_recipe: u8,
+    ) -> ProgramResult {
+        Ok(())
+    }
+
+    #[access_control(is_admin(&ctx.accounts.staking_account, &ctx.accounts.admin))]
+    pub fn set_destination(
+        ctx: Context<SetDestination>,
+        _nonce_staking: u8,
+        destination_id: u32,
+        _nonce_destination: u8,
+        config: DestinationConfig,
+    ) -> ProgramResult {
+        assert_destination_config_valid(&config)?;
+
+        let destination_account = &mut ctx.accounts.destination_account;
+        destination_account.destination_id = destination_id;
+        destination_account.config = config;
+
+        Ok(())
+    }
+
+    #[access_control(is_admin(&ctx.accounts.staking_account, &ctx.accounts.admin))]
+    pub fn open_round(
+        ctx: Context<OpenRound>,
+        _nonce_staking: u8,
+        round_id: u32,
+        _nonce_round: u8,
+        enrollment_end: u64,
+        departure_at: u64,
+        return_at: u64,
+    ) -> ProgramResult {
+        // enrollment opens now and closes no later than the departure
+        let now = Clock::get()?.unix_timestamp as u64;
+        if !(now < enrollment_end && enrollment_end <= departure_at && departure_at < return_at) {
+            return Err(ErrorCode::InvalidRound.into());
+        }
+
+        let round_account = &mut ctx.accounts.round_account;
+        round_account.round_id = round_id;
+        round_account.enrollment_start = now;
+        round_account.enrollment_end = enrollment_end;
+        round_account.departure_at = departure_at;
+        round_account.return_at = return_at;
+
+        Ok(())
+    }
+
+    #[access_control(is_admin(&ctx.accounts.staking_account, &ctx.accounts.admin))]
+    pub fn cancel_round(
+        ctx: Context<CancelRound>,
+        _nonce_staking: u8,
+        _round_id: u32,
+        _nonce_round: u8,
+    ) -> ProgramResult {
+        // a returned round has nothing left to abort
+        if Clock::get()?.unix_timestamp as u64 >= ctx.accounts.round_account.return_at {
+            return Err(ErrorCode::InvalidRound.into());
+        }
+
+        ctx.accounts.round_account.cancelled = true;
+
+        Ok(())
+    }
+
+    #[access_control(is_admin(&ctx.accounts.staking_account, &ctx.accounts.admin))]
+    pub fn remove_destination(
+        ctx: Context<RemoveDestination>,
+        _nonce_staking: u8,
+        _destination_id: u32,
+        _nonce_destination: u8,
+    ) -> ProgramResult {
+        // running expeditions settle against their destination
+        if ctx.accounts.destination_account.active_positions > 0 {
+            return Err(ErrorCode::DestinationInUse.into());
+        }
+
+        Ok(())
+    }
+
     // maximum size is 10
     #[access_control(is_admin(&ctx.accounts.staking_account, &ctx.accounts.admin))]
     pub fn remove_reward<'a, 'b, 'c, 'info>(
@@ -205,6 +821,9 @@ pub mod nft_staking {
         while index < remaining_accounts_length {
             let nft_mint = &remaining_accounts[index];
 
+            // determine the token program of the reward
+            assert_token_program(nft_mint, &ctx.accounts.token_program)?;
+
             match ctx
                 .accounts
                 .staking_account
@@ -247,6 +866,7 @@ pub mod nft_staking {
     pub fn add_winner<'a, 'b, 'c, 'info>(
         ctx: Context<'a, 'b, 'c, 'info, AddWinner<'info>>,
         _nonce_staking: u8,
+        _nonce_mint_blocklist: u8,
         winner_staking_index: Vec<u32>,
         winner: Vec<Pubkey>
     ) -> ProgramResult {
@@ -279,6 +899,12 @@ pub mod nft_staking {
                 return Err(ErrorCode::DifferentWallet.into());
             }
 
+            // determine if any staked nft is blocked
+            assert_staked_nfts_not_blocked(
+                &user_staking_account.nft_mint_keys,
+                &ctx.accounts.mint_blocklist_account,
+            )?;
+
             // Check if nft is one of the rewards
             if ctx
                 .accounts
@@ -322,6 +948,7 @@ pub mod nft_staking {
         ctx: Context<'a, 'b, 'c, 'info, AddAuryWinner<'info>>,
         _nonce_staking: u8,
         _nonce_aury_vault: u8,
+        _nonce_mint_blocklist: u8,
         winner_staking_index: Vec<u32>,
         winner: Vec<Pubkey>,
         aury_amount: Vec<u64>,
@@ -348,10 +975,17 @@ pub mod nft_staking {
                 return Err(ErrorCode::InvalidAccounts.into());
             }
 
+            // determine if any staked nft is blocked
+            assert_staked_nfts_not_blocked(
+                &user_staking_account.nft_mint_keys,
+                &ctx.accounts.mint_blocklist_account,
+            )?;
+
             // transfer aury to the vault
             spl_token_transfer(TokenTransferParams {
                 source: ctx.accounts.aury_from.to_account_info(),
                 destination: ctx.accounts.aury_vault.to_account_info(),
+                mint: ctx.accounts.aury_mint.to_account_info(),
                 amount: aury_amount[index],
                 authority: ctx.accounts.admin.to_account_info(),
                 authority_signer_seeds: &[],
@@ -368,13 +1002,15 @@ pub mod nft_staking {
         Ok(())
     }
 
-    // maximum size is 4
+    // maximum size is 2
     pub fn stake<'a, 'b, 'c, 'info>(
         ctx: Context<'a, 'b, 'c, 'info, Stake<'info>>,
         nonce_nft_vault: Vec<u8>,
-        _nonce_staking: u8,
+        nonce_staking: u8,
         _nonce_user_staking_counter: u8,
         _nonce_user_staking: u8,
+        _nonce_eligibility_rules: u8,
+        _nonce_mint_blocklist: u8,
     ) -> ProgramResult {
         //init_if_needed safety check
         {
@@ -400,12 +1036,24 @@ pub mod nft_staking {
             return Err(ErrorCode::StakingLocked.into());
         }
 
+        // determine the wallet's concurrent expeditions
+        let wallet_positions = load_wallet_positions(
+            ctx.program_id,
+            &ctx.accounts.wallet_positions_account,
+            ctx.accounts.nft_from_authority.key,
+        )?;
+        if ctx.accounts.staking_account.max_wallet_positions > 0
+            && wallet_positions.active_positions >= ctx.accounts.staking_account.max_wallet_positions
+        {
+            return Err(ErrorCode::WalletExpeditionsFull.into());
+        }
+
         // determine the remaining accounts
         let remaining_accounts = ctx.remaining_accounts;
         let remaining_accounts_length = ctx.remaining_accounts.len();
 
-        if remaining_accounts_length % 4 != 0
-        || nonce_nft_vault.len() != remaining_accounts_length / 4 {
+        if remaining_accounts_length % 8 != 0
+        || nonce_nft_vault.len() != remaining_accounts_length / 8 {
             return Err(ErrorCode::InvalidAccounts.into());
         }
 
@@ -413,78 +1061,313 @@ pub mod nft_staking {
         let owner = &ctx.accounts.staking_account;
         let system_program = &ctx.accounts.system_program;
         let token_program = &ctx.accounts.token_program;
+        let token_metadata_program = &ctx.accounts.token_metadata_program;
         let rent = &ctx.accounts.rent;
+        // compute staking account signer seeds
+        let staking_account_seeds = &[constants::STAKING_PDA_SEED, &[nonce_staking]];
+        let staking_account_signer = &staking_account_seeds[..];
 
         let mut index = 0;
         while index < remaining_accounts_length {
             let nft_mint = &remaining_accounts[index];
             let nft_metadata = &remaining_accounts[index + 1];
-            let nft_from = Account::<'_, TokenAccount>::try_from(&remaining_accounts[index + 2])?;
+            let nft_from = &remaining_accounts[index + 2];
             let nft_vault = &remaining_accounts[index + 3];
-
-            assert_metadata_valid(
+            let aurorian_registry_account =
+                load_aurorian_registry(ctx.program_id, &remaining_accounts[index + 4], nft_mint.key)?;
+            let nft_edition = &remaining_accounts[index + 5];
+            let aurorian_stats =
+                load_aurorian_stats(ctx.program_id, &remaining_accounts[index + 6], nft_mint.key)?;
+            let stake_record = &remaining_accounts[index + 7];
+
+            let metadata = assert_metadata_valid(
                 nft_metadata,
+                nft_edition,
                 nft_mint.key,
                 ctx.accounts.staking_account.clone(),
+                &ctx.accounts.eligibility_rules_account,
+                &aurorian_stats,
             )?;
 
-            // init if needed nft vault
-            if nft_vault.owner == &token_program.key() {
-                let nft_vault_token_account = Account::<'_, TokenAccount>::try_from(&nft_vault)?;
+            // determine the token program of the nft
+            assert_token_program(nft_mint, token_program)?;
 
-                // validate the existing nft vault
-                if nft_vault_token_account.mint != *nft_mint.key
-                    || nft_vault_token_account.owner != owner.key()
-                    || nft_vault_token_account.state != AccountState::Initialized
-                {
-                    return Err(ErrorCode::InvalidAccounts.into());
-                }
-            } else {
-                // compute nft vault account signer seeds
-                let nft_vault_account_seeds = &[
-                    nft_from_authority.key.as_ref(),
-                    nft_mint.key.as_ref(),
-                    &[nonce_nft_vault[index / 4]],
-                ];
-                let nft_vault_account_signer = &nft_vault_account_seeds[..];
+            // determine if the nft is blocked
+            assert_not_blocked(nft_mint.key, &ctx.accounts.mint_blocklist_account)?;
 
-                // initialize nft vault account
-                spl_init_token_account(InitializeTokenAccountParams {
-                    account: nft_vault.clone(),
-                    account_signer_seeds: nft_vault_account_signer,
-                    mint: nft_mint.clone(),
+            // determine if the aurorian is resting from its last expedition
+            if (Clock::get()?.unix_timestamp as u64) < aurorian_stats.cooldown_until {
+                return Err(ErrorCode::AurorianInCooldown.into());
+            }
+
+            // determine the registered rarity, unregistered aurorians stake at the lowest one
+            if aurorian_registry_account.rarity < ctx.accounts.staking_account.minimum_rarity {
+                return Err(ErrorCode::RarityTooLow.into());
+            }
+
+            // passing the owner's token account as the vault keeps the nft in the wallet
+            let non_custodial = nft_vault.key == nft_from.key;
+
+            if non_custodial {
+                // metaplex only freezes classic spl token accounts
+                if token_program.key() != spl_token::id() {
+                    return Err(ErrorCode::NonCustodialTokenProgramUnsupported.into());
+                }
+                if unpack_token_account(nft_from)?.mint != *nft_mint.key {
+                    return Err(ErrorCode::InvalidAccounts.into());
+                }
+
+                // delegate the nft to the staking account
+                spl_approve(ApproveParams {
+                    source: nft_from.to_account_info(),
+                    delegate: owner.to_account_info(),
+                    owner: nft_from_authority.to_account_info(),
+                    amount: 1,
+                    token_program: token_program.to_account_info(),
+                })?;
+
+                // freeze the nft in place
+                metadata_freeze_delegated_account(DelegatedAccountParams {
+                    delegate: owner.to_account_info(),
+                    delegate_signer_seeds: staking_account_signer,
+                    token_account: nft_from.to_account_info(),
+                    edition: nft_edition.clone(),
+                    mint: nft_mint.clone(),
+                    token_program: token_program.to_account_info(),
+                    token_metadata_program: token_metadata_program.to_account_info(),
+                })?;
+            } else {
+                // init if needed nft vault
+                if nft_vault.owner == &token_program.key() {
+                    let nft_vault_token_account = unpack_token_account(nft_vault)?;
+
+                    // validate the existing nft vault
+                    if nft_vault_token_account.mint != *nft_mint.key
+                        || nft_vault_token_account.owner != owner.key()
+                        || nft_vault_token_account.state != AccountState::Initialized
+                    {
+                        return Err(ErrorCode::InvalidAccounts.into());
+                    }
+                } else {
+                    // compute nft vault account signer seeds
+                    let nft_vault_account_seeds = &[
+                        nft_from_authority.key.as_ref(),
+                        nft_mint.key.as_ref(),
+                        &[nonce_nft_vault[index / 8]],
+                    ];
+                    let nft_vault_account_signer = &nft_vault_account_seeds[..];
+
+                    // initialize nft vault account
+                    spl_init_token_account(InitializeTokenAccountParams {
+                        account: nft_vault.clone(),
+                        account_signer_seeds: nft_vault_account_signer,
+                        mint: nft_mint.clone(),
+                        owner: owner.to_account_info(),
+                        payer: nft_from_authority.to_account_info(),
+                        system_program: system_program.to_account_info(),
+                        token_program: token_program.to_account_info(),
+                        rent: rent.to_account_info(),
+                    })?;
+                }
+
+                // transfer nft to nft vault
+                spl_token_transfer(TokenTransferParams {
+                    source: nft_from.to_account_info(),
+                    destination: nft_vault.clone(),
+                    mint: nft_mint.clone(),
+                    authority: nft_from_authority.to_account_info(),
+                    authority_signer_seeds: &[],
+                    token_program: token_program.to_account_info(),
+                    amount: 1,
+                })?;
+            }
+
+            // record the stake, an existing record means the nft is already staked
+            init_stake_record(StakeRecordParams {
+                stake_record: StakeRecordAccount {
+                    nft_mint: *nft_mint.key,
+                    wallet: *nft_from_authority.key,
+                    user_staking_index: ctx.accounts.user_staking_counter_account.counter,
+                    vault: *nft_vault.key,
+                    staked_at: Clock::get()?.unix_timestamp as u64,
+                },
+                stake_record_info: stake_record.clone(),
+                payer: nft_from_authority.to_account_info(),
+                system_program: system_program.to_account_info(),
+                program_id: ctx.program_id,
+            })?;
+
+            // push nft_mint_key to the nft_mint_keys and its data to the staked_nfts
+            ctx.accounts
+                .user_staking_account
+                .nft_mint_keys
+                .push(*nft_mint.key);
+            ctx.accounts
+                .user_staking_account
+                .staked_nfts
+                .push(StakedNft {
+                    nft_mint: *nft_mint.key,
+                    rarity: aurorian_registry_account.rarity,
+                    traits: aurorian_registry_account.traits,
+                    non_custodial,
+                    // metaplex pads the name and symbol with null characters
+                    name: metadata.data.name.trim_end_matches(char::from(0)).to_string(),
+                    symbol: metadata.data.symbol.trim_end_matches(char::from(0)).to_string(),
+                    primary_creator: primary_verified_creator_of(&metadata),
+                    collection: verified_collection_of(&metadata),
+                });
+
+            index += 8;
+        }
+
+        Ok(())
+    }
+
+    // maximum size is 5
+    pub fn stake_items<'info>(
+        ctx: Context<'_, '_, '_, 'info, StakeItems<'info>>,
+        nonce_item_vault: Vec<u8>,
+        amounts: Vec<u64>,
+        _nonce_staking: u8,
+        _nonce_user_staking_counter: u8,
+        _nonce_user_staking: u8,
+    ) -> ProgramResult {
+        // determine if stake is locked
+        if ctx.accounts.user_staking_account.staking_period > 0 {
+            return Err(ErrorCode::StakingLocked.into());
+        }
+
+        // determine the wallet's concurrent expeditions
+        let wallet_positions = load_wallet_positions(
+            ctx.program_id,
+            &ctx.accounts.wallet_positions_account,
+            ctx.accounts.nft_from_authority.key,
+        )?;
+        if ctx.accounts.staking_account.max_wallet_positions > 0
+            && wallet_positions.active_positions >= ctx.accounts.staking_account.max_wallet_positions
+        {
+            return Err(ErrorCode::WalletExpeditionsFull.into());
+        }
+
+        // determine the remaining accounts
+        let remaining_accounts = ctx.remaining_accounts;
+        let remaining_accounts_length = ctx.remaining_accounts.len();
+
+        if remaining_accounts_length == 0
+            || remaining_accounts_length % 3 != 0
+            || nonce_item_vault.len() != remaining_accounts_length / 3
+            || amounts.len() != remaining_accounts_length / 3
+        {
+            return Err(ErrorCode::InvalidAccounts.into());
+        }
+
+        let item_from_authority = &ctx.accounts.nft_from_authority;
+        let owner = &ctx.accounts.staking_account;
+        let system_program = &ctx.accounts.system_program;
+        let token_program = &ctx.accounts.token_program;
+        let rent = &ctx.accounts.rent;
+
+        let mut index = 0;
+        while index < remaining_accounts_length {
+            let item_mint = &remaining_accounts[index];
+            let item_from = &remaining_accounts[index + 1];
+            let item_vault = &remaining_accounts[index + 2];
+            let amount = amounts[index / 3];
+
+            if amount == 0 {
+                return Err(ErrorCode::InvalidItemAmount.into());
+            }
+
+            // determine the token program of the item
+            assert_token_program(item_mint, token_program)?;
+
+            // Check if item is one of the rewards
+            if !ctx.accounts.staking_account.active_rewards.contains(item_mint.key) {
+                return Err(ErrorCode::InvalidMintForReward.into());
+            }
+
+            // init if needed item vault
+            if item_vault.owner == &token_program.key() {
+                // determine the item vault is the wallet's
+                assert_derivation(
+                    ctx.program_id,
+                    item_vault,
+                    &[item_from_authority.key.as_ref(), item_mint.key.as_ref()],
+                )?;
+                let item_vault_token_account = unpack_token_account(item_vault)?;
+
+                // validate the existing item vault
+                if item_vault_token_account.mint != *item_mint.key
+                    || item_vault_token_account.owner != owner.key()
+                    || item_vault_token_account.state != AccountState::Initialized
+                {
+                    return Err(ErrorCode::InvalidAccounts.into());
+                }
+            } else {
+                // compute item vault account signer seeds
+                let item_vault_account_seeds = &[
+                    item_from_authority.key.as_ref(),
+                    item_mint.key.as_ref(),
+                    &[nonce_item_vault[index / 3]],
+                ];
+                let item_vault_account_signer = &item_vault_account_seeds[..];
+
+                // initialize item vault account
+                spl_init_token_account(InitializeTokenAccountParams {
+                    account: item_vault.clone(),
+                    account_signer_seeds: item_vault_account_signer,
+                    mint: item_mint.clone(),
                     owner: owner.to_account_info(),
-                    payer: nft_from_authority.to_account_info(),
+                    payer: item_from_authority.to_account_info(),
                     system_program: system_program.to_account_info(),
                     token_program: token_program.to_account_info(),
                     rent: rent.to_account_info(),
                 })?;
             }
 
-            // transfer nft to nft vault
+            // transfer items to item vault
             spl_token_transfer(TokenTransferParams {
-                source: nft_from.to_account_info(),
-                destination: nft_vault.clone(),
-                authority: nft_from_authority.to_account_info(),
+                source: item_from.clone(),
+                destination: item_vault.clone(),
+                mint: item_mint.clone(),
+                authority: item_from_authority.to_account_info(),
                 authority_signer_seeds: &[],
                 token_program: token_program.to_account_info(),
-                amount: 1,
+                amount,
             })?;
 
-            // push nft_mint_key from the nft_mint_keys
-            ctx.accounts
-                .user_staking_account
-                .nft_mint_keys
-                .push(*nft_mint.key);
+            let staked_items = &mut ctx.accounts.user_staking_account.staked_items;
+            match staked_items
+                .iter()
+                .position(|staked_item| staked_item.nft_mint == *item_mint.key)
+            {
+                Some(index) => {
+                    staked_items[index].amount = staked_items[index]
+                        .amount
+                        .checked_add(amount)
+                        .ok_or(ErrorCode::NumericalOverflow)?;
+                }
+                None => {
+                    staked_items.push(StakedItem {
+                        nft_mint: *item_mint.key,
+                        amount,
+                    });
+                }
+            }
+
+            index += 3;
+        }
 
-            index += 4;
+        if ctx.accounts.user_staking_account.staked_items.len() > MAX_STAKED_ITEMS {
+            return Err(ErrorCode::TooManyStakedItems.into());
         }
 
         Ok(())
     }
 
-    pub fn lock_stake(
-        ctx: Context<LockStake>,
+    // maximum size is 4
+    pub fn lock_stake<'info>(
+        ctx: Context<'_, '_, '_, 'info, LockStake<'info>>,
         _nonce_staking: u8,
         _nonce_user_staking_counter: u8,
         _nonce_user_staking: u8,
@@ -497,6 +1380,72 @@ pub mod nft_staking {
             return Err(ErrorCode::StakingLocked.into());
         }
 
+        // the round to enroll in, if any, is passed as the first remaining account
+        let remaining_accounts = ctx.remaining_accounts;
+        let remaining_accounts_length = ctx.remaining_accounts.len();
+        let mut round_account = match remaining_accounts.first() {
+            Some(account) => Account::<'_, RoundAccount>::try_from(account).ok(),
+            None => None,
+        };
+
+        // enrolled positions depart and return with the round
+        let now = Clock::get()?.unix_timestamp as u64;
+        let (staking_at, staking_period) = match &round_account {
+            Some(round_account) => {
+                if round_account.cancelled
+                    || now < round_account.enrollment_start
+                    || now >= round_account.enrollment_end
+                {
+                    return Err(ErrorCode::RoundNotOpen.into());
+                }
+                (
+                    round_account.departure_at,
+                    round_account.return_at - round_account.departure_at,
+                )
+            }
+            None => (now, staking_period),
+        };
+
+        // determine the party size and composition
+        let staked_nfts = staked_nfts_of(&ctx.accounts.user_staking_account);
+        assert_party_valid(&ctx.accounts.party_rules_account.party_rules, &staked_nfts)?;
+
+        // determine the destination requirements
+        let destination_config = &ctx.accounts.destination_account.config;
+        assert_party_valid(&destination_config.party_rules, &staked_nfts)?;
+        if !destination_config.allowed_durations.is_empty()
+            && !destination_config.allowed_durations.contains(&staking_period)
+        {
+            return Err(ErrorCode::InvalidStakingPeriod.into());
+        }
+        if destination_config.capacity > 0
+            && ctx.accounts.destination_account.active_positions >= destination_config.capacity
+        {
+            return Err(ErrorCode::DestinationFull.into());
+        }
+        let entry_fee = destination_config.entry_fee;
+
+        // determine the expedition-wide capacity
+        let party_size = staked_nfts.len() as u32;
+        let staking_account = &ctx.accounts.staking_account;
+        if (staking_account.max_active_positions > 0
+            && staking_account.active_positions >= staking_account.max_active_positions)
+            || (staking_account.max_active_nfts > 0
+                && staking_account.active_nfts + party_size > staking_account.max_active_nfts)
+        {
+            return Err(ErrorCode::ExpeditionFull.into());
+        }
+        let mut wallet_positions = load_wallet_positions(
+            ctx.program_id,
+            &ctx.accounts.wallet_positions_account,
+            ctx.accounts.nft_from_authority.key,
+        )?;
+        if staking_account.max_wallet_positions > 0
+            && wallet_positions.active_positions >= staking_account.max_wallet_positions
+        {
+            return Err(ErrorCode::WalletExpeditionsFull.into());
+        }
+
         // determine the staking period
         if !(staking_period >= ctx.accounts.staking_account.minimum_staking_period
             && staking_period <= ctx.accounts.staking_account.maximum_staking_period)
@@ -504,18 +1453,135 @@ pub mod nft_staking {
             return Err(ErrorCode::InvalidStakingPeriod.into());
         }
 
+        // the position settles under the terms it started with
+        let config = PositionConfig {
+            version: POSITION_CONFIG_VERSION,
+            minimum_staking_period: ctx.accounts.staking_account.minimum_staking_period,
+            maximum_staking_period: ctx.accounts.staking_account.maximum_staking_period,
+            allowed_durations: ctx.accounts.destination_account.config.allowed_durations.clone(),
+            rarity_multipliers: ctx.accounts.staking_account.rarity_multipliers.clone(),
+            aury_emission_rates: ctx.accounts.staking_account.aury_emission_rates.clone(),
+            expedition_cooldown: ctx.accounts.staking_account.expedition_cooldown,
+            entry_fee,
+            loot_table_id: ctx.accounts.destination_account.config.loot_table_id,
+        };
+
+        // equip the staked items whose reward configs are passed as the remaining accounts
+        let mut aury_boost: u64 = 0;
+        let mut odds_boost: u64 = 0;
+        let mut index = if round_account.is_some() { 1 } else { 0 };
+        while index < remaining_accounts_length {
+            let reward_config_account =
+                Account::<'_, RewardConfigAccount>::try_from(&remaining_accounts[index])?;
+            let equipment = reward_config_account.equipment;
+
+            if equipment.slot == 0 {
+                return Err(ErrorCode::NotEquipment.into());
+            }
+
+            // determine if the item is staked on the position
+            if !ctx
+                .accounts
+                .user_staking_account
+                .staked_items
+                .iter()
+                .any(|staked_item| staked_item.nft_mint == reward_config_account.nft_mint)
+            {
+                return Err(ErrorCode::NotStakedItem.into());
+            }
+
+            // one item per slot
+            if ctx
+                .accounts
+                .user_staking_account
+                .equipment
+                .iter()
+                .any(|equipped| equipped.slot == equipment.slot)
+            {
+                return Err(ErrorCode::EquipmentSlotTaken.into());
+            }
+
+            ctx.accounts.user_staking_account.equipment.push(Equipment {
+                nft_mint: reward_config_account.nft_mint,
+                slot: equipment.slot,
+            });
+            aury_boost += equipment.aury_boost as u64;
+            odds_boost += equipment.odds_boost as u64;
+
+            index += 1;
+        }
+
+        if ctx.accounts.user_staking_account.equipment.len() > MAX_EQUIPMENT {
+            return Err(ErrorCode::TooManyEquipment.into());
+        }
+
+        // determine the aury emission rate with the synergies matched by the party and reserve the
+        // emission from the pool
+        let synergy_multiplier =
+            synergy_multiplier_for(&ctx.accounts.synergies_account.synergies, &staked_nfts);
+        let aury_emission_rate = aury_emission_rate_of(
+            &config,
+            &staked_nfts,
+            aury_boost,
+            synergy_multiplier,
+            staking_period,
+        )?;
+        let aury_emission_reserve = aury_emission_rate
+            .checked_mul(staking_period)
+            .ok_or(ErrorCode::NumericalOverflow)?;
+
+        // the entry fee funds the emission pool
+        ctx.accounts.staking_account.aury_emission_pool = ctx
+            .accounts
+            .staking_account
+            .aury_emission_pool
+            .checked_add(entry_fee)
+            .ok_or(ErrorCode::NumericalOverflow)?;
+
+        if aury_emission_reserve > ctx.accounts.staking_account.aury_emission_pool {
+            return Err(ErrorCode::InsufficientAuryEmissionPool.into());
+        }
+        ctx.accounts.staking_account.aury_emission_pool -= aury_emission_reserve;
+
         // set user staking info
         ctx.accounts.user_staking_account.index = ctx.accounts.user_staking_counter_account.counter;
         ctx.accounts.user_staking_account.wallet = *ctx.accounts.nft_from_authority.key;
-        ctx.accounts.user_staking_account.staking_at = Clock::get()?.unix_timestamp as u64;
+        ctx.accounts.user_staking_account.staking_at = staking_at;
         ctx.accounts.user_staking_account.staking_period = staking_period;
+        ctx.accounts.user_staking_account.aury_emission_rate = aury_emission_rate;
+        ctx.accounts.user_staking_account.last_aury_accrual_at = staking_at;
+        ctx.accounts.user_staking_account.aury_boost = aury_boost;
+        ctx.accounts.user_staking_account.odds_boost = odds_boost;
+        ctx.accounts.user_staking_account.synergy_multiplier = synergy_multiplier;
+        ctx.accounts.user_staking_account.destination_id =
+            ctx.accounts.destination_account.destination_id;
+        ctx.accounts.user_staking_account.config = config;
+        ctx.accounts.destination_account.active_positions += 1;
+        ctx.accounts.staking_account.active_positions += 1;
+        ctx.accounts.staking_account.active_nfts += party_size;
         ctx.accounts.user_staking_counter_account.counter += 1;
 
+        wallet_positions.active_positions += 1;
+        save_wallet_positions(WalletPositionsParams {
+            wallet_positions: &wallet_positions,
+            wallet_positions_info: ctx.accounts.wallet_positions_account.to_account_info(),
+            payer: ctx.accounts.nft_from_authority.to_account_info(),
+            system_program: ctx.accounts.system_program.to_account_info(),
+            program_id: ctx.program_id,
+        })?;
+
+        if let Some(round_account) = &mut round_account {
+            ctx.accounts.user_staking_account.round_id = Some(round_account.round_id);
+            round_account.enrolled_positions += 1;
+            round_account.exit(&id())?;
+        }
+
         if aury_amount != 0 {
             // transfer aury to the vault
             spl_token_transfer(TokenTransferParams {
                 source: ctx.accounts.aury_from.to_account_info(),
                 destination: ctx.accounts.aury_vault.to_account_info(),
+                mint: ctx.accounts.aury_mint.to_account_info(),
                 amount: aury_amount,
                 authority: ctx.accounts.nft_from_authority.to_account_info(),
                 authority_signer_seeds: &[],
@@ -525,77 +1591,346 @@ pub mod nft_staking {
             // update user staking info
             ctx.accounts.user_staking_account.aury_deposit = aury_amount;
         }
-        
+
+        if entry_fee != 0 {
+            // transfer the entry fee to the vault
+            spl_token_transfer(TokenTransferParams {
+                source: ctx.accounts.aury_from.to_account_info(),
+                destination: ctx.accounts.aury_vault.to_account_info(),
+                mint: ctx.accounts.aury_mint.to_account_info(),
+                amount: entry_fee,
+                authority: ctx.accounts.nft_from_authority.to_account_info(),
+                authority_signer_seeds: &[],
+                token_program: ctx.accounts.token_program.to_account_info(),
+            })?;
+        }
 
         Ok(())
     }
 
-    // maximum size is 5
+    pub fn extend_expedition(
+        ctx: Context<ExtendExpedition>,
+        _nonce_staking: u8,
+        _user_staking_index: u32,
+        _nonce_user_staking: u8,
+        _nonce_aury_vault: u8,
+        additional_period: u64,
+        aury_amount: u64,
+    ) -> ProgramResult {
+        // determine if stake is locked
+        if ctx.accounts.user_staking_account.staking_period == 0 {
+            return Err(ErrorCode::StakingNotLocked.into());
+        }
+        if ctx.accounts.user_staking_account.round_id.is_some() {
+            return Err(ErrorCode::RoundReturnFixed.into());
+        }
+
+        // determine if the expedition is still running
+        let now = Clock::get()?.unix_timestamp as u64;
+        let user_staking_account = &ctx.accounts.user_staking_account;
+        if now >= user_staking_account.staking_at + user_staking_account.staking_period {
+            return Err(ErrorCode::ExpeditionFinished.into());
+        }
+
+        // the extension is held to the terms the expedition started with, the whole expedition must
+        // last one of the allowed durations
+        let config = position_config_of(user_staking_account, &ctx.accounts.staking_account);
+        let staking_period = user_staking_account
+            .staking_period
+            .checked_add(additional_period)
+            .ok_or(ErrorCode::NumericalOverflow)?;
+        if additional_period == 0
+            || staking_period < config.minimum_staking_period
+            || staking_period > config.maximum_staking_period
+            || (!config.allowed_durations.is_empty()
+                && !config.allowed_durations.contains(&staking_period))
+        {
+            return Err(ErrorCode::InvalidStakingPeriod.into());
+        }
+
+        // keep the aury emitted so far at the current rate
+        let user_staking_account = &mut ctx.accounts.user_staking_account;
+        accrue_aury_emission(user_staking_account)?;
+
+        // move the rate to the tier the new period reaches, boosts used on the expedition carry over
+        let tier_rate =
+            aury_emission_rate_for(&config.aury_emission_rates, user_staking_account.staking_period);
+        let extended_tier_rate = aury_emission_rate_for(&config.aury_emission_rates, staking_period);
+        let aury_emission_rate = if tier_rate > 0 {
+            user_staking_account
+                .aury_emission_rate
+                .checked_mul(extended_tier_rate)
+                .ok_or(ErrorCode::NumericalOverflow)?
+                / tier_rate
+        } else {
+            aury_emission_rate_of(
+                &config,
+                &user_staking_account.staked_nfts,
+                user_staking_account.aury_boost,
+                user_staking_account.synergy_multiplier,
+                staking_period,
+            )?
+        };
+
+        // reserve the emission of the rest of the extended expedition in place of the current one
+        let emitting_from = now.max(user_staking_account.last_aury_accrual_at);
+        let return_at = user_staking_account.staking_at + user_staking_account.staking_period;
+        let aury_emission_reserved = user_staking_account
+            .aury_emission_rate
+            .checked_mul(return_at.saturating_sub(emitting_from))
+            .ok_or(ErrorCode::NumericalOverflow)?;
+        let aury_emission_reserve = aury_emission_rate
+            .checked_mul((return_at + additional_period).saturating_sub(emitting_from))
+            .ok_or(ErrorCode::NumericalOverflow)?;
+
+        let staking_account = &mut ctx.accounts.staking_account;
+        staking_account.aury_emission_pool = staking_account
+            .aury_emission_pool
+            .checked_add(aury_emission_reserved)
+            .ok_or(ErrorCode::NumericalOverflow)?
+            .checked_sub(aury_emission_reserve)
+            .ok_or(ErrorCode::InsufficientAuryEmissionPool)?;
+
+        // update user staking info
+        let user_staking_account = &mut ctx.accounts.user_staking_account;
+        user_staking_account.staking_period = staking_period;
+        user_staking_account.aury_emission_rate = aury_emission_rate;
+        user_staking_account.aury_deposit = user_staking_account
+            .aury_deposit
+            .checked_add(aury_amount)
+            .ok_or(ErrorCode::NumericalOverflow)?;
+
+        if aury_amount != 0 {
+            // transfer the additional deposit to the vault
+            spl_token_transfer(TokenTransferParams {
+                source: ctx.accounts.aury_from.to_account_info(),
+                destination: ctx.accounts.aury_vault.to_account_info(),
+                mint: ctx.accounts.aury_mint.to_account_info(),
+                amount: aury_amount,
+                authority: ctx.accounts.nft_from_authority.to_account_info(),
+                authority_signer_seeds: &[],
+                token_program: ctx.accounts.token_program.to_account_info(),
+            })?;
+        }
+
+        Ok(())
+    }
+
+    // maximum size is 4
     pub fn unstake<'a, 'b, 'c, 'info>(
         ctx: Context<'a, 'b, 'c, 'info, Unstake<'info>>,
         nonce_staking: u8,
         _user_staking_index: u32,
         _nonce_user_staking: u8,
     ) -> ProgramResult {
-        // determine if claimable is empty
-        if ctx.accounts.user_staking_account.claimable.len() > 0
-            || ctx.accounts.user_staking_account.claimable_aury_amount > 0
+        // the round of the position, if any, is passed as the first remaining account
+        let (round_cancelled, remaining_accounts) = match ctx
+            .remaining_accounts
+            .first()
+            .map(Account::<'_, RoundAccount>::try_from)
         {
-            return Err(ErrorCode::CantUnstakeBeforeClaim.into());
-        }
+            Some(Ok(round_account)) => {
+                if ctx.accounts.user_staking_account.round_id != Some(round_account.round_id) {
+                    return Err(ErrorCode::InvalidRound.into());
+                }
+                (round_account.cancelled, &ctx.remaining_accounts[1..])
+            }
+            _ => (false, ctx.remaining_accounts),
+        };
 
         // determine if stake is locked
         if ctx.accounts.user_staking_account.staking_period == 0 {
             return Err(ErrorCode::StakingNotLocked.into());
         }
 
+        // a cancelled round releases its positions with the deposit refunded
+        if round_cancelled {
+            release_cancelled_position(
+                &mut ctx.accounts.staking_account,
+                &mut ctx.accounts.user_staking_account,
+            )?;
+        }
+
+        // determine if claimable is empty, rewards removed since they were won stay claimable
+        if ctx
+            .accounts
+            .user_staking_account
+            .claimable
+            .iter()
+            .any(|claimable_token| {
+                ctx.accounts.staking_account.active_rewards.contains(&claimable_token.nft_mint)
+            })
+            || (!round_cancelled && ctx.accounts.user_staking_account.claimable_aury_amount > 0)
+            || (ctx.accounts.user_staking_account.aury_emission_rate > 0
+                && ctx.accounts.user_staking_account.last_aury_accrual_at
+                    < ctx.accounts.user_staking_account.staking_at
+                        + ctx.accounts.user_staking_account.staking_period)
+        {
+            return Err(ErrorCode::CantUnstakeBeforeClaim.into());
+        }
+
         // determine the staking period
-        if (Clock::get()?.unix_timestamp as u64 - ctx.accounts.user_staking_account.staking_at)
-            < ctx.accounts.user_staking_account.staking_period
+        if !round_cancelled
+            && (Clock::get()?.unix_timestamp as u64)
+                < ctx.accounts.user_staking_account.staking_at
+                    + ctx.accounts.user_staking_account.staking_period
         {
             return Err(ErrorCode::StakingLocked.into());
         }
 
         // determine the remaining accounts
-        let remaining_accounts = ctx.remaining_accounts;
-        let remaining_accounts_length = ctx.remaining_accounts.len();
-
-        if remaining_accounts_length % 2 != 0
-            || remaining_accounts_length / 2 > ctx.accounts.user_staking_account.nft_mint_keys.len()
+        let remaining_accounts_length = remaining_accounts.len();
+        let returning = !ctx.accounts.user_staking_account.nft_mint_keys.is_empty()
+            || !ctx.accounts.user_staking_account.staked_items.is_empty();
+        let mut returned_nfts: u32 = 0;
+
+        // aurorians take 6 accounts and items 4
+        if remaining_accounts_length
+            > ctx.accounts.user_staking_account.nft_mint_keys.len() * 6
+                + ctx.accounts.user_staking_account.staked_items.len() * 4
         {
             return Err(ErrorCode::InvalidAccounts.into());
         }
 
+        let config =
+            position_config_of(&ctx.accounts.user_staking_account, &ctx.accounts.staking_account);
         let authority = &ctx.accounts.staking_account;
         let nft_to_authority = &ctx.accounts.nft_to_authority;
+        let system_program = &ctx.accounts.system_program;
         let token_program = &ctx.accounts.token_program;
+        let token_metadata_program = &ctx.accounts.token_metadata_program;
         // compute staking account signer seeds
         let staking_account_seeds = &[constants::STAKING_PDA_SEED.as_ref(), &[nonce_staking]];
         let staking_account_signer = &staking_account_seeds[..];
 
         let mut index = 0;
         while index < remaining_accounts_length {
-            let nft_to = Account::<'_, TokenAccount>::try_from(&remaining_accounts[index])?;
-            let mut nft_vault = Account::<'_, TokenAccount>::try_from(&remaining_accounts[index + 1])?;
+            if index + 4 > remaining_accounts_length {
+                return Err(ErrorCode::InvalidAccounts.into());
+            }
 
-            match ctx
+            let nft_mint = &remaining_accounts[index];
+            let nft_edition = &remaining_accounts[index + 1];
+            let nft_to = &remaining_accounts[index + 2];
+            let nft_vault = &remaining_accounts[index + 3];
+
+            // determine the token program of the nft
+            assert_token_program(nft_mint, token_program)?;
+
+            if unpack_token_account(nft_vault)?.mint != *nft_mint.key {
+                return Err(ErrorCode::InvalidAccounts.into());
+            }
+
+            let nft_mint_index = ctx
                 .accounts
                 .user_staking_account
                 .nft_mint_keys
                 .iter()
-                .position(|&mint_key| mint_key == nft_vault.mint)
-            {
-                Some(index) => {
-                    // remove staked nft key
-                    ctx.accounts
-                        .user_staking_account
-                        .nft_mint_keys
-                        .remove(index);
+                .position(|nft_mint_key| nft_mint_key == nft_mint.key);
+            // nfts staked before the registry have neither data nor a stake record
+            let staked_nft = ctx
+                .accounts
+                .user_staking_account
+                .staked_nfts
+                .iter()
+                .find(|staked_nft| staked_nft.nft_mint == *nft_mint.key);
+            let rarity = staked_nft.map_or(0, |staked_nft| staked_nft.rarity);
+            let non_custodial = staked_nft.map_or(false, |staked_nft| staked_nft.non_custodial);
+
+            if nft_mint_index.is_some() {
+                if index + 6 > remaining_accounts_length {
+                    return Err(ErrorCode::InvalidAccounts.into());
+                }
+
+                // close the stake record
+                if staked_nft.is_some() {
+                    close_stake_record(
+                        ctx.program_id,
+                        &remaining_accounts[index + 5],
+                        nft_mint.key,
+                        &ctx.accounts.user_staking_account,
+                        nft_to_authority.to_account_info(),
+                    )?;
+                }
+
+                let aurorian_stats_info = &remaining_accounts[index + 4];
+                let mut aurorian_stats =
+                    load_aurorian_stats(ctx.program_id, aurorian_stats_info, nft_mint.key)?;
+                let staking_period = ctx.accounts.user_staking_account.staking_period;
+                let xp = aurorian_xp_for(
+                    &config.rarity_multipliers,
+                    rarity,
+                    staking_period,
+                )?;
+
+                // record the completed expedition, an aborted one doesn't count nor rest
+                if !round_cancelled {
+                    aurorian_stats.expeditions_completed = aurorian_stats
+                        .expeditions_completed
+                        .checked_add(1)
+                        .ok_or(ErrorCode::NumericalOverflow)?;
+                    aurorian_stats.total_locked_time = aurorian_stats
+                        .total_locked_time
+                        .checked_add(staking_period)
+                        .ok_or(ErrorCode::NumericalOverflow)?;
+                    aurorian_stats.xp = aurorian_stats
+                        .xp
+                        .checked_add(xp)
+                        .ok_or(ErrorCode::NumericalOverflow)?;
+                    aurorian_stats.level = aurorian_level_for(aurorian_stats.xp);
+                    aurorian_stats.cooldown_until = (Clock::get()?.unix_timestamp as u64)
+                        .checked_add(config.expedition_cooldown)
+                        .ok_or(ErrorCode::NumericalOverflow)?;
+                }
+
+                save_aurorian_stats(AurorianStatsParams {
+                    aurorian_stats: &aurorian_stats,
+                    aurorian_stats_info: aurorian_stats_info.clone(),
+                    payer: nft_to_authority.to_account_info(),
+                    system_program: system_program.to_account_info(),
+                    program_id: ctx.program_id,
+                })?;
+
+                returned_nfts += 1;
+            }
+
+            match nft_mint_index {
+                Some(_) if non_custodial => {
+                    // remove staked nft
+                    remove_staked_nft(&mut ctx.accounts.user_staking_account, nft_mint.key);
+
+                    // the nft never left the owner's token account
+                    if nft_vault.key != nft_to.key {
+                        return Err(ErrorCode::InvalidAccounts.into());
+                    }
+
+                    // thaw the nft in place
+                    metadata_thaw_delegated_account(DelegatedAccountParams {
+                        delegate: authority.to_account_info(),
+                        delegate_signer_seeds: staking_account_signer,
+                        token_account: nft_to.to_account_info(),
+                        edition: nft_edition.clone(),
+                        mint: nft_mint.clone(),
+                        token_program: token_program.to_account_info(),
+                        token_metadata_program: token_metadata_program.to_account_info(),
+                    })?;
+
+                    // revoke the staking account delegation
+                    spl_revoke(RevokeParams {
+                        source: nft_to.to_account_info(),
+                        owner: nft_to_authority.to_account_info(),
+                        token_program: token_program.to_account_info(),
+                    })?;
+                }
+                Some(_) => {
+                    // remove staked nft
+                    remove_staked_nft(&mut ctx.accounts.user_staking_account, nft_mint.key);
 
                     // transfer nft to user
                     spl_token_transfer(TokenTransferParams {
                         source: nft_vault.to_account_info(),
                         destination: nft_to.to_account_info(),
+                        mint: nft_mint.clone(),
                         authority: authority.to_account_info(),
                         authority_signer_seeds: staking_account_signer,
                         token_program: token_program.to_account_info(),
@@ -603,9 +1938,7 @@ pub mod nft_staking {
                     })?;
 
                     // Close nft_vault tokenAccount
-                    (&mut nft_vault).reload()?;
-
-                    if nft_vault.amount == 0 {
+                    if unpack_token_account(nft_vault)?.amount == 0 {
                         spl_close_account(CloseAccountParams {
                             account: nft_vault.to_account_info(),
                             destination: nft_to_authority.to_account_info(),
@@ -616,12 +1949,106 @@ pub mod nft_staking {
                     }
                 }
                 None => {
-                    return Err(ErrorCode::NotStakedItem.into());
-                }
-            }
-
-            index += 2;
-        }
+                    let staked_item = match ctx
+                        .accounts
+                        .user_staking_account
+                        .staked_items
+                        .iter()
+                        .position(|staked_item| staked_item.nft_mint == *nft_mint.key)
+                    {
+                        // remove staked item
+                        Some(index) => ctx.accounts.user_staking_account.staked_items.remove(index),
+                        None => {
+                            return Err(ErrorCode::NotStakedItem.into());
+                        }
+                    };
+
+                    // determine the item vault staked into by the wallet
+                    assert_derivation(
+                        ctx.program_id,
+                        nft_vault,
+                        &[nft_to_authority.key.as_ref(), nft_mint.key.as_ref()],
+                    )?;
+                    let item_vault_token_account = unpack_token_account(nft_vault)?;
+                    if item_vault_token_account.mint != *nft_mint.key
+                        || item_vault_token_account.owner != authority.key()
+                    {
+                        return Err(ErrorCode::InvalidAccounts.into());
+                    }
+
+                    // transfer items to user
+                    spl_token_transfer(TokenTransferParams {
+                        source: nft_vault.to_account_info(),
+                        destination: nft_to.to_account_info(),
+                        mint: nft_mint.clone(),
+                        authority: authority.to_account_info(),
+                        authority_signer_seeds: staking_account_signer,
+                        token_program: token_program.to_account_info(),
+                        amount: staked_item.amount,
+                    })?;
+
+                    // Close item vault tokenAccount
+                    if unpack_token_account(nft_vault)?.amount == 0 {
+                        spl_close_account(CloseAccountParams {
+                            account: nft_vault.to_account_info(),
+                            destination: nft_to_authority.to_account_info(),
+                            owner: authority.to_account_info(),
+                            owner_signer_seeds: staking_account_signer,
+                            token_program: token_program.to_account_info(),
+                        })?;
+                    }
+                }
+            }
+
+            index += if nft_mint_index.is_some() { 6 } else { 4 };
+        }
+
+        // positions locked before the counters existed aren't counted
+        if ctx.accounts.user_staking_account.config.version > 0 {
+            ctx.accounts.staking_account.active_nfts =
+                ctx.accounts.staking_account.active_nfts.saturating_sub(returned_nfts);
+        }
+
+        // the expedition leaves the destination once everything is returned
+        if returning
+            && ctx.accounts.user_staking_account.nft_mint_keys.is_empty()
+            && ctx.accounts.user_staking_account.staked_items.is_empty()
+        {
+            // positions locked before the counters, destinations and wallet positions existed aren't counted
+            if ctx.accounts.user_staking_account.config.version > 0 {
+                let destination_info = &ctx.accounts.destination_account;
+                assert_derivation(
+                    ctx.program_id,
+                    destination_info,
+                    &[
+                        constants::DESTINATION_PDA_SEED,
+                        ctx.accounts.user_staking_account.destination_id.to_string().as_ref(),
+                    ],
+                )?;
+                let mut destination_account =
+                    Account::<'_, DestinationAccount>::try_from(destination_info)?;
+                destination_account.active_positions =
+                    destination_account.active_positions.saturating_sub(1);
+                destination_account.exit(ctx.program_id)?;
+
+                let mut wallet_positions = load_wallet_positions(
+                    ctx.program_id,
+                    &ctx.accounts.wallet_positions_account,
+                    ctx.accounts.nft_to_authority.key,
+                )?;
+                wallet_positions.active_positions = wallet_positions.active_positions.saturating_sub(1);
+                save_wallet_positions(WalletPositionsParams {
+                    wallet_positions: &wallet_positions,
+                    wallet_positions_info: ctx.accounts.wallet_positions_account.to_account_info(),
+                    payer: ctx.accounts.nft_to_authority.to_account_info(),
+                    system_program: ctx.accounts.system_program.to_account_info(),
+                    program_id: ctx.program_id,
+                })?;
+
+                ctx.accounts.staking_account.active_positions =
+                    ctx.accounts.staking_account.active_positions.saturating_sub(1);
+            }
+        }
 
         // close account if it's empty
         // if ctx.accounts.user_staking_account.nft_mint_keys.len() == 0 {
@@ -638,14 +2065,57 @@ pub mod nft_staking {
         _nonce_user_staking: u8,
     ) -> ProgramResult {
         // determine if claimable is empty
-        if ctx.accounts.user_staking_account.nft_mint_keys.len() > 0
+        if !ctx.accounts.user_staking_account.nft_mint_keys.is_empty()
+            || !ctx.accounts.user_staking_account.staked_items.is_empty()
         {
             return Err(ErrorCode::CantCloseBeforeUnstake.into());
         }
 
+        // determine if everything owed is claimed, a cancelled round refunds the deposit as claimable aury
+        // and the won rewards removed since can't be claimed, they're dropped with the account
+        let user_staking_account = &ctx.accounts.user_staking_account;
+        if user_staking_account
+            .claimable
+            .iter()
+            .any(|claimable_token| {
+                ctx.accounts.staking_account.active_rewards.contains(&claimable_token.nft_mint)
+            })
+            || user_staking_account.claimable_aury_amount > 0
+            || (user_staking_account.aury_emission_rate > 0
+                && user_staking_account.last_aury_accrual_at
+                    < user_staking_account.staking_at + user_staking_account.staking_period)
+        {
+            return Err(ErrorCode::CantCloseBeforeClaim.into());
+        }
+
         Ok(())
     }
 
+    #[access_control(is_migration_admin(&ctx.accounts.staking_account, &ctx.accounts.admin))]
+    pub fn migrate_staking(ctx: Context<MigrateStaking>, _nonce_staking: u8) -> ProgramResult {
+        // grow the staking account created before the expedition settings to the current layout
+        realloc_account(
+            &ctx.accounts.admin.to_account_info(),
+            STAKING_SPACE,
+            &ctx.accounts.system_program.to_account_info(),
+            &ctx.accounts.staking_account,
+        )
+    }
+
+    pub fn migrate_user_staking<'info>(
+        ctx: Context<'_, '_, '_, 'info, MigrateUserStaking<'info>>,
+        _user_staking_index: u32,
+        _nonce_user_staking: u8,
+    ) -> ProgramResult {
+        // grow the accounts staked before the registry to the current layout
+        realloc_account(
+            &ctx.accounts.nft_from_authority.to_account_info(),
+            USER_STAKING_SPACE,
+            &ctx.accounts.system_program.to_account_info(),
+            &ctx.accounts.user_staking_account,
+        )
+    }
+
     // maximum size is 5
     pub fn claim<'a, 'b, 'c, 'info>(
         ctx: Context<'a, 'b, 'c, 'info, Claim<'info>>,
@@ -665,7 +2135,10 @@ pub mod nft_staking {
         let mut index = 0;
         while index < remaining_accounts_length {
             let nft_mint = &remaining_accounts[index];
-            let nft_to = Account::<'_, TokenAccount>::try_from(&remaining_accounts[index + 1])?;
+            let nft_to = &remaining_accounts[index + 1];
+
+            // determine the token program of the reward
+            assert_token_program(nft_mint, &ctx.accounts.token_program)?;
 
             match ctx
                 .accounts
@@ -674,24 +2147,23 @@ pub mod nft_staking {
                 .iter()
                 .position(|claimable_token| claimable_token.nft_mint == *nft_mint.key)
             {
-                Some(index) => {
-                    let claimable_token = ctx.accounts.user_staking_account.claimable[index];
+                Some(claimable_index) => {
+                    let claimable_token = ctx.accounts.user_staking_account.claimable[claimable_index];
 
-                    // remove claimed item from user
-                    ctx.accounts.user_staking_account.claimable.remove(index);
-
-                    // check if claim token is active reward
-                    if ctx
+                    // skip a removed reward, it's kept until it's added back
+                    if !ctx
                         .accounts
                         .staking_account
                         .active_rewards
-                        .iter()
-                        .find(|&&active_reward| active_reward == claimable_token.nft_mint)
-                        == None
+                        .contains(&claimable_token.nft_mint)
                     {
+                        index += 2;
                         continue;
                     }
 
+                    // remove claimed item from user
+                    ctx.accounts.user_staking_account.claimable.remove(claimable_index);
+
                     // compute staking account signer seeds
                     let staking_account_seeds =
                         &[constants::STAKING_PDA_SEED.as_ref(), &[nonce_staking]];
@@ -724,6 +2196,16 @@ pub mod nft_staking {
         _user_staking_index: u32,
         _nonce_user_staking: u8,
     ) -> ProgramResult {
+        // a blocked nft forfeits the emission not accrued yet
+        forfeit_blocked_aury_emission(
+            &mut ctx.accounts.staking_account,
+            &mut ctx.accounts.user_staking_account,
+            &ctx.accounts.mint_blocklist_account,
+        )?;
+
+        // accrue the aury emitted since the last claim
+        accrue_aury_emission(&mut ctx.accounts.user_staking_account)?;
+
         if ctx.accounts.user_staking_account.claimable_aury_amount > 0 {
             // compute aury vault account signer seeds
             let aury_mint_key = ctx.accounts.aury_mint.key();
@@ -734,6 +2216,7 @@ pub mod nft_staking {
             spl_token_transfer(TokenTransferParams {
                 source: ctx.accounts.aury_vault.to_account_info(),
                 destination: ctx.accounts.aury_to.to_account_info(),
+                mint: ctx.accounts.aury_mint.to_account_info(),
                 amount: ctx.accounts.user_staking_account.claimable_aury_amount,
                 authority: ctx.accounts.aury_vault.to_account_info(),
                 authority_signer_seeds: aury_vault_account_signer,
@@ -746,6 +2229,207 @@ pub mod nft_staking {
         Ok(())
     }
 
+    pub fn use_boost(
+        ctx: Context<UseBoost>,
+        _nonce_staking: u8,
+        _user_staking_index: u32,
+        _nonce_user_staking: u8,
+        _nonce_reward_config: u8,
+        amount: u64,
+    ) -> ProgramResult {
+        let consumable = ctx.accounts.reward_config_account.consumable;
+
+        if consumable.lock_reduction == 0 && consumable.reward_multiplier == 0 {
+            return Err(ErrorCode::NotConsumable.into());
+        }
+        if consumable.lock_reduction > 0 && ctx.accounts.user_staking_account.round_id.is_some() {
+            return Err(ErrorCode::RoundReturnFixed.into());
+        }
+        if amount == 0 {
+            return Err(ErrorCode::InvalidItemAmount.into());
+        }
+
+        // determine if stake is locked
+        if ctx.accounts.user_staking_account.staking_period == 0 {
+            return Err(ErrorCode::StakingNotLocked.into());
+        }
+
+        // determine if the expedition is still running
+        let now = Clock::get()?.unix_timestamp as u64;
+        let staking_at = ctx.accounts.user_staking_account.staking_at;
+        if now >= staking_at + ctx.accounts.user_staking_account.staking_period {
+            return Err(ErrorCode::ExpeditionFinished.into());
+        }
+
+        // determine the token program of the boost
+        assert_token_program(&ctx.accounts.boost_mint, &ctx.accounts.token_program)?;
+
+        // burn the consumed boosts
+        spl_token_burn(TokenBurnParams {
+            mint: ctx.accounts.boost_mint.to_account_info(),
+            source: ctx.accounts.boost_from.to_account_info(),
+            amount,
+            authority: ctx.accounts.boost_from_authority.to_account_info(),
+            token_program: ctx.accounts.token_program.to_account_info(),
+        })?;
+
+        // a blocked nft forfeits the emission not accrued yet
+        forfeit_blocked_aury_emission(
+            &mut ctx.accounts.staking_account,
+            &mut ctx.accounts.user_staking_account,
+            &ctx.accounts.mint_blocklist_account,
+        )?;
+
+        // accrue the aury emitted at the current rate before changing it
+        accrue_aury_emission(&mut ctx.accounts.user_staking_account)?;
+
+        let user_staking_account = &mut ctx.accounts.user_staking_account;
+        let staking_account = &mut ctx.accounts.staking_account;
+
+        if consumable.lock_reduction > 0 {
+            // shorten the lock, never before now
+            let lock_reduction = consumable
+                .lock_reduction
+                .checked_mul(amount)
+                .ok_or(ErrorCode::NumericalOverflow)?;
+            let staking_period = user_staking_account
+                .staking_period
+                .saturating_sub(lock_reduction)
+                .max(now.saturating_sub(staking_at));
+
+            // return the emission reserved for the removed time to the pool
+            let aury_emission_refund = user_staking_account
+                .aury_emission_rate
+                .checked_mul(
+                    user_staking_account
+                        .staking_period
+                        .checked_sub(staking_period)
+                        .ok_or(ErrorCode::NumericalOverflow)?,
+                )
+                .ok_or(ErrorCode::NumericalOverflow)?;
+            staking_account.aury_emission_pool = staking_account
+                .aury_emission_pool
+                .checked_add(aury_emission_refund)
+                .ok_or(ErrorCode::NumericalOverflow)?;
+
+            user_staking_account.staking_period = staking_period;
+        }
+
+        if consumable.reward_multiplier > 0 {
+            // raise the emission rate for the remaining time and reserve it from the pool
+            let reward_multiplier = (consumable.reward_multiplier as u64)
+                .checked_mul(amount)
+                .ok_or(ErrorCode::NumericalOverflow)?;
+            let aury_emission_rate = user_staking_account
+                .aury_emission_rate
+                .checked_mul(
+                    BASIS_POINTS
+                        .checked_add(reward_multiplier)
+                        .ok_or(ErrorCode::NumericalOverflow)?,
+                )
+                .ok_or(ErrorCode::NumericalOverflow)?
+                / BASIS_POINTS;
+            let aury_emission_reserve = aury_emission_rate
+                .checked_sub(user_staking_account.aury_emission_rate)
+                .ok_or(ErrorCode::NumericalOverflow)?
+                .checked_mul(staking_at + user_staking_account.staking_period - now.max(staking_at))
+                .ok_or(ErrorCode::NumericalOverflow)?;
+
+            staking_account.aury_emission_pool = staking_account
+                .aury_emission_pool
+                .checked_sub(aury_emission_reserve)
+                .ok_or(ErrorCode::InsufficientAuryEmissionPool)?;
+
+            user_staking_account.aury_emission_rate = aury_emission_rate;
+        }
+
+        Ok(())
+    }
+
+    // maximum size is 5
+    pub fn craft<'info>(
+        ctx: Context<'_, '_, '_, 'info, Craft<'info>>,
+        nonce_staking: u8,
+        _recipe_id: u32,
+        _nonce_recipe: u8,
+    ) -> ProgramResult {
+        let recipe_account = &ctx.accounts.recipe_account;
+
+        // determine the remaining accounts
+        let remaining_accounts = ctx.remaining_accounts;
+        let remaining_accounts_length = ctx.remaining_accounts.len();
+
+        if remaining_accounts_length != recipe_account.inputs.len() * 2 {
+            return Err(ErrorCode::InvalidAccounts.into());
+        }
+
+        let mut index = 0;
+        while index < remaining_accounts_length {
+            let input_mint = &remaining_accounts[index];
+            let input_from = &remaining_accounts[index + 1];
+            let input = recipe_account.inputs[index / 2];
+
+            if *input_mint.key != input.nft_mint {
+                return Err(ErrorCode::InvalidAccounts.into());
+            }
+
+            // determine the token program of the input
+            assert_token_program(input_mint, &ctx.accounts.token_program)?;
+
+            // burn the input
+            spl_token_burn(TokenBurnParams {
+                mint: input_mint.clone(),
+                source: input_from.clone(),
+                amount: input.amount,
+                authority: ctx.accounts.crafter.to_account_info(),
+                token_program: ctx.accounts.token_program.to_account_info(),
+            })?;
+
+            index += 2;
+        }
+
+        // Check if the output is still one of the rewards
+        if !ctx
+            .accounts
+            .staking_account
+            .active_rewards
+            .contains(&recipe_account.output_mint)
+        {
+            return Err(ErrorCode::InvalidMintForReward.into());
+        }
+
+        // determine the token program of the output
+        assert_token_program(&ctx.accounts.output_mint, &ctx.accounts.token_program)?;
+
+        // determine the supply cap of the output
+        if recipe_account.output_supply_cap > 0 {
+            let output_supply = unpack_mint(&ctx.accounts.output_mint)?
+                .supply
+                .checked_add(recipe_account.output_amount)
+                .ok_or(ErrorCode::NumericalOverflow)?;
+
+            if output_supply > recipe_account.output_supply_cap {
+                return Err(ErrorCode::SupplyCapExceeded.into());
+            }
+        }
+
+        // compute staking account signer seeds
+        let staking_account_seeds = &[constants::STAKING_PDA_SEED, &[nonce_staking]];
+        let staking_account_signer = &staking_account_seeds[..];
+
+        // mint the output to the crafter
+        spl_token_mint(TokenMintParams {
+            mint: ctx.accounts.output_mint.to_account_info(),
+            to: ctx.accounts.output_to.to_account_info(),
+            amount: recipe_account.output_amount,
+            owner: ctx.accounts.staking_account.to_account_info(),
+            owner_signer_seeds: staking_account_signer,
+            token_program: ctx.accounts.token_program.to_account_info(),
+        })?;
+
+        Ok(())
+    }
+
     #[access_control(is_admin(&ctx.accounts.staking_account, &ctx.accounts.admin))]
     pub fn mint_to(ctx: Context<MintTo>, nonce_staking: u8, amount: u64) -> ProgramResult {
         if ctx
@@ -759,123 +2443,511 @@ pub mod nft_staking {
             return Err(ErrorCode::InvalidMintForReward.into());
         }
 
+        // determine the token program of the reward
+        assert_token_program(&ctx.accounts.nft_mint, &ctx.accounts.token_program)?;
+
         // compute staking account signer seeds
         let staking_account_seeds = &[constants::STAKING_PDA_SEED.as_ref(), &[nonce_staking]];
         let staking_account_signer = &staking_account_seeds[..];
 
-        // mint claimable amounts to user
-        spl_token_mint(TokenMintParams {
-            mint: ctx.accounts.nft_mint.to_account_info(),
-            to: ctx.accounts.nft_to.to_account_info(),
-            amount: amount,
-            owner: ctx.accounts.staking_account.to_account_info(),
-            owner_signer_seeds: staking_account_signer,
-            token_program: ctx.accounts.token_program.to_account_info(),
-        })?;
+        // mint claimable amounts to user
+        spl_token_mint(TokenMintParams {
+            mint: ctx.accounts.nft_mint.to_account_info(),
+            to: ctx.accounts.nft_to.to_account_info(),
+            amount: amount,
+            owner: ctx.accounts.staking_account.to_account_info(),
+            owner_signer_seeds: staking_account_signer,
+            token_program: ctx.accounts.token_program.to_account_info(),
+        })?;
+
+        Ok(())
+    }
+
+    // Allows to transfer any spl token owned by the staking account. Used to retrieve wrongly sent airdrops.
+    #[access_control(is_admin(&ctx.accounts.staking_account, &ctx.accounts.admin))]
+    pub fn transfer_to(ctx: Context<TransferTo>, nonce_staking: u8, amount: u64) -> ProgramResult {
+        // compute staking account signer seeds
+        let staking_account_seeds = &[constants::STAKING_PDA_SEED.as_ref(), &[nonce_staking]];
+        let staking_account_signer = &staking_account_seeds[..];
+
+        // transfer aury from vault
+        spl_token_transfer(TokenTransferParams {
+            source: ctx.accounts.source_token_account.to_account_info(),
+            destination: ctx.accounts.dest_token_account.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+            amount: amount,
+            authority: ctx.accounts.staking_account.to_account_info(),
+            authority_signer_seeds: staking_account_signer,
+            token_program: ctx.accounts.token_program.to_account_info(),
+        })?;
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+#[instruction(nonce_staking: u8)]
+pub struct TransferTo<'info> {
+    #[account(
+        mut,
+        constraint = source_token_account.owner == token_program.key,
+    )]
+    pub source_token_account: AccountInfo<'info>,
+    #[account(mut)]
+    pub dest_token_account: AccountInfo<'info>,
+    #[account(constraint = mint.owner == token_program.key)]
+    pub mint: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds = [ constants::STAKING_PDA_SEED.as_ref() ],
+        bump = nonce_staking,
+        constraint = !staking_account.freeze_program,
+    )]
+    pub staking_account: Box<Account<'info, StakingAccount>>,
+
+    pub admin: Signer<'info>,
+
+    #[account(constraint = is_token_program(token_program.key))]
+    pub token_program: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(_nonce_staking: u8, nonce_aury_vault: u8)]
+pub struct Initialize<'info> {
+    #[account(
+        init,
+        payer = initializer,
+        seeds = [ constants::STAKING_PDA_SEED.as_ref() ],
+        bump = _nonce_staking,
+        space = STAKING_SPACE,
+    )]
+    pub staking_account: Box<Account<'info, StakingAccount>>,
+
+    #[account(
+        address = constants::AURY_TOKEN_MINT_PUBKEY.parse::<Pubkey>().unwrap(),
+        constraint = aury_mint.owner == token_program.key,
+    )]
+    pub aury_mint: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds = [ constants::AURY_TOKEN_MINT_PUBKEY.parse::<Pubkey>().unwrap().as_ref() ],
+        bump = nonce_aury_vault,
+    )]
+    pub aury_vault: AccountInfo<'info>,
+
+    #[account(mut)]
+    pub initializer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+    #[account(constraint = is_token_program(token_program.key))]
+    pub token_program: AccountInfo<'info>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+#[instruction(_nonce_staking: u8)]
+pub struct FreezeProgram<'info> {
+    #[account(
+        mut,
+        seeds = [ constants::STAKING_PDA_SEED.as_ref() ],
+        bump = _nonce_staking,
+    )]
+    pub staking_account: Box<Account<'info, StakingAccount>>,
+
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(_nonce_staking: u8)]
+pub struct UpdateAdmin<'info> {
+    #[account(
+        mut,
+        seeds = [ constants::STAKING_PDA_SEED.as_ref() ],
+        bump = _nonce_staking,
+    )]
+    pub staking_account: Box<Account<'info, StakingAccount>>,
+
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(_nonce_staking: u8)]
+pub struct UpdateAuthorizedCreator<'info> {
+    #[account(
+        mut,
+        seeds = [ constants::STAKING_PDA_SEED.as_ref() ],
+        bump = _nonce_staking,
+    )]
+    pub staking_account: Box<Account<'info, StakingAccount>>,
+
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(_nonce_staking: u8)]
+pub struct AddAuthorizedCreators<'info> {
+    #[account(
+        mut,
+        seeds = [ constants::STAKING_PDA_SEED ],
+        bump = _nonce_staking,
+    )]
+    pub staking_account: Box<Account<'info, StakingAccount>>,
+
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(_nonce_staking: u8)]
+pub struct RemoveAuthorizedCreators<'info> {
+    #[account(
+        mut,
+        seeds = [ constants::STAKING_PDA_SEED ],
+        bump = _nonce_staking,
+    )]
+    pub staking_account: Box<Account<'info, StakingAccount>>,
+
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(_nonce_staking: u8)]
+pub struct UpdateStakingPeriod<'info> {
+    #[account(
+        mut,
+        seeds = [ constants::STAKING_PDA_SEED.as_ref() ],
+        bump = _nonce_staking,
+    )]
+    pub staking_account: Box<Account<'info, StakingAccount>>,
+
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(_nonce_staking: u8)]
+pub struct UpdateExpeditionCooldown<'info> {
+    #[account(
+        mut,
+        seeds = [ constants::STAKING_PDA_SEED ],
+        bump = _nonce_staking,
+    )]
+    pub staking_account: Box<Account<'info, StakingAccount>>,
+
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(_nonce_staking: u8)]
+pub struct UpdateCapacity<'info> {
+    #[account(
+        mut,
+        seeds = [ constants::STAKING_PDA_SEED ],
+        bump = _nonce_staking,
+    )]
+    pub staking_account: Box<Account<'info, StakingAccount>>,
+
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(_nonce_staking: u8)]
+pub struct UpdateAuryEmissionRates<'info> {
+    #[account(
+        mut,
+        seeds = [ constants::STAKING_PDA_SEED ],
+        bump = _nonce_staking,
+    )]
+    pub staking_account: Box<Account<'info, StakingAccount>>,
+
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(_nonce_staking: u8, _nonce_aury_vault: u8)]
+pub struct FundAuryEmission<'info> {
+    #[account(
+        mut,
+        seeds = [ constants::STAKING_PDA_SEED ],
+        bump = _nonce_staking,
+    )]
+    pub staking_account: Box<Account<'info, StakingAccount>>,
+
+    #[account(
+        address = constants::AURY_TOKEN_MINT_PUBKEY.parse::<Pubkey>().unwrap(),
+        constraint = aury_mint.owner == token_program.key,
+    )]
+    pub aury_mint: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds = [ aury_mint.key().as_ref() ],
+        bump = _nonce_aury_vault,
+    )]
+    pub aury_vault: AccountInfo<'info>,
+
+    #[account(mut)]
+    pub aury_from: AccountInfo<'info>,
+
+    pub admin: Signer<'info>,
+
+    #[account(constraint = is_token_program(token_program.key))]
+    pub token_program: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(_nonce_staking: u8)]
+pub struct UpdateRarityConfig<'info> {
+    #[account(
+        mut,
+        seeds = [ constants::STAKING_PDA_SEED ],
+        bump = _nonce_staking,
+    )]
+    pub staking_account: Box<Account<'info, StakingAccount>>,
+
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(_nonce_staking: u8, _nonce_aurorian_registry: u8)]
+pub struct SetAurorianRegistry<'info> {
+    #[account(
+        seeds = [ constants::STAKING_PDA_SEED ],
+        bump = _nonce_staking,
+    )]
+    pub staking_account: Box<Account<'info, StakingAccount>>,
+
+    #[account(constraint = is_token_program(nft_mint.owner))]
+    pub nft_mint: AccountInfo<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = admin,
+        seeds = [ constants::AURORIAN_REGISTRY_PDA_SEED, nft_mint.key().as_ref() ],
+        bump = _nonce_aurorian_registry,
+        // 8: account's signature on the anchor
+        // 32: nft_mint
+        // 1: rarity
+        // 4: traits
+        space = 8 + 32 + 1 + 4,
+    )]
+    pub aurorian_registry_account: Box<Account<'info, AurorianRegistryAccount>>,
+
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+#[instruction(_nonce_staking: u8, _nonce_aurorian_registry: u8)]
+pub struct RemoveAurorianRegistry<'info> {
+    #[account(
+        seeds = [ constants::STAKING_PDA_SEED ],
+        bump = _nonce_staking,
+    )]
+    pub staking_account: Box<Account<'info, StakingAccount>>,
+
+    #[account(
+        mut,
+        close = admin,
+        seeds = [ constants::AURORIAN_REGISTRY_PDA_SEED, aurorian_registry_account.nft_mint.as_ref() ],
+        bump = _nonce_aurorian_registry,
+    )]
+    pub aurorian_registry_account: Box<Account<'info, AurorianRegistryAccount>>,
+
+    #[account(mut)]
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(_nonce_staking: u8)]
+pub struct AddAuthorizedNameStarts<'info> {
+    #[account(
+        mut,
+        seeds = [ constants::STAKING_PDA_SEED.as_ref() ],
+        bump = _nonce_staking,
+    )]
+    pub staking_account: Box<Account<'info, StakingAccount>>,
+
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(_nonce_staking: u8)]
+pub struct RemoveAuthorizedNameStarts<'info> {
+    #[account(
+        mut,
+        seeds = [ constants::STAKING_PDA_SEED.as_ref() ],
+        bump = _nonce_staking,
+    )]
+    pub staking_account: Box<Account<'info, StakingAccount>>,
+
+    pub admin: Signer<'info>,
+}
 
-        Ok(())
-    }
+#[derive(Accounts)]
+#[instruction(_nonce_staking: u8)]
+pub struct AddAuthorizedCollections<'info> {
+    #[account(
+        mut,
+        seeds = [ constants::STAKING_PDA_SEED ],
+        bump = _nonce_staking,
+    )]
+    pub staking_account: Box<Account<'info, StakingAccount>>,
 
-    // Allows to transfer any spl token owned by the staking account. Used to retrieve wrongly sent airdrops.
-    #[access_control(is_admin(&ctx.accounts.staking_account, &ctx.accounts.admin))]
-    pub fn transfer_to(ctx: Context<TransferTo>, nonce_staking: u8, amount: u64) -> ProgramResult {
-        // compute staking account signer seeds
-        let staking_account_seeds = &[constants::STAKING_PDA_SEED.as_ref(), &[nonce_staking]];
-        let staking_account_signer = &staking_account_seeds[..];
+    pub admin: Signer<'info>,
+}
 
-        // transfer aury from vault
-        spl_token_transfer(TokenTransferParams {
-            source: ctx.accounts.source_token_account.to_account_info(),
-            destination: ctx.accounts.dest_token_account.to_account_info(),
-            amount: amount,
-            authority: ctx.accounts.staking_account.to_account_info(),
-            authority_signer_seeds: staking_account_signer,
-            token_program: ctx.accounts.token_program.to_account_info(),
-        })?;
-        Ok(())
-    }
+#[derive(Accounts)]
+#[instruction(_nonce_staking: u8)]
+pub struct RemoveAuthorizedCollections<'info> {
+    #[account(
+        mut,
+        seeds = [ constants::STAKING_PDA_SEED ],
+        bump = _nonce_staking,
+    )]
+    pub staking_account: Box<Account<'info, StakingAccount>>,
+
+    pub admin: Signer<'info>,
 }
 
 #[derive(Accounts)]
-#[instruction(nonce_staking: u8)]
-pub struct TransferTo<'info> {
-    #[account(mut)]
-    pub source_token_account: Box<Account<'info, TokenAccount>>,
+#[instruction(_nonce_staking: u8, _nonce_eligibility_rules: u8)]
+pub struct SetEligibilityRules<'info> {
+    #[account(
+        seeds = [ constants::STAKING_PDA_SEED ],
+        bump = _nonce_staking,
+    )]
+    pub staking_account: Box<Account<'info, StakingAccount>>,
+
+    #[account(
+        init_if_needed,
+        payer = admin,
+        seeds = [ constants::ELIGIBILITY_RULES_PDA_SEED ],
+        bump = _nonce_eligibility_rules,
+        // 8: account's signature on the anchor
+        // 4: rules Vec's length
+        // 2048: serialized rules
+        space = 8 + 4 + 2048,
+    )]
+    pub eligibility_rules_account: Box<Account<'info, EligibilityRulesAccount>>,
+
     #[account(mut)]
-    pub dest_token_account: Box<Account<'info, TokenAccount>>,
+    pub admin: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
 
+#[derive(Accounts)]
+#[instruction(_nonce_staking: u8, _nonce_party_rules: u8)]
+pub struct SetPartyRules<'info> {
     #[account(
-        mut,
-        seeds = [ constants::STAKING_PDA_SEED.as_ref() ],
-        bump = nonce_staking,
-        constraint = !staking_account.freeze_program,
+        seeds = [ constants::STAKING_PDA_SEED ],
+        bump = _nonce_staking,
     )]
     pub staking_account: Box<Account<'info, StakingAccount>>,
 
+    #[account(
+        init_if_needed,
+        payer = admin,
+        seeds = [ constants::PARTY_RULES_PDA_SEED ],
+        bump = _nonce_party_rules,
+        // 8: account's signature on the anchor
+        // 1: min_party_size
+        // 1: max_party_size
+        // 4: constraints Vec's length
+        // (1 + 4 + 32 + 1 + 1) * 8: constraints limit 8 and prefix max_length 32
+        space = 8 + 1 + 1 + 4 + (1 + 4 + 32 + 1 + 1) * 8,
+    )]
+    pub party_rules_account: Box<Account<'info, PartyRulesAccount>>,
+
+    #[account(mut)]
     pub admin: Signer<'info>,
 
-    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
 }
 
 #[derive(Accounts)]
-#[instruction(_nonce_staking: u8, _nonce_aury_vault: u8)]
-pub struct Initialize<'info> {
+#[instruction(_nonce_staking: u8, _nonce_synergies: u8)]
+pub struct SetSynergies<'info> {
     #[account(
-        init,
-        payer = initializer,
-        seeds = [ constants::STAKING_PDA_SEED.as_ref() ],
+        seeds = [ constants::STAKING_PDA_SEED ],
         bump = _nonce_staking,
-        // 8: account's signature on the anchor
-        // 32: admin_key
-        // 1: freeze_program
-        // 32: authorized_creator
-        // 4: authorized_name_starts Vec's length
-        // 32 * 150: authorized_name_starts limit 150 and max_length 32
-        // 4: active_rewards Vec's length
-        // 32 * 150: active_rewards limit 150
-        space = 8 + 32 + 1 + 32 + 4 + 32 * 150 + 4 + 32 * 150
     )]
     pub staking_account: Box<Account<'info, StakingAccount>>,
 
     #[account(
-        address = constants::AURY_TOKEN_MINT_PUBKEY.parse::<Pubkey>().unwrap(),
+        init_if_needed,
+        payer = admin,
+        seeds = [ constants::SYNERGIES_PDA_SEED ],
+        bump = _nonce_synergies,
+        // 8: account's signature on the anchor
+        // 4: synergies Vec's length
+        // (1 + 4 + 32 + 1 + 2) * 8: synergies limit 8 and prefix max_length 32
+        space = 8 + 4 + (1 + 4 + 32 + 1 + 2) * 8,
+    )]
+    pub synergies_account: Box<Account<'info, SynergiesAccount>>,
+
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+#[instruction(_nonce_staking: u8, _nonce_mint_blocklist: u8)]
+pub struct AddBlockedMints<'info> {
+    #[account(
+        seeds = [ constants::STAKING_PDA_SEED ],
+        bump = _nonce_staking,
     )]
-    pub aury_mint: Box<Account<'info, Mint>>,
+    pub staking_account: Box<Account<'info, StakingAccount>>,
 
     #[account(
-        init,
-        payer = initializer,
-        token::mint = aury_mint,
-        token::authority = aury_vault,
-        seeds = [ constants::AURY_TOKEN_MINT_PUBKEY.parse::<Pubkey>().unwrap().as_ref() ],
-        bump = _nonce_aury_vault,
+        init_if_needed,
+        payer = admin,
+        seeds = [ constants::MINT_BLOCKLIST_PDA_SEED ],
+        bump = _nonce_mint_blocklist,
+        // 8: account's signature on the anchor
+        // 4: blocked_mints Vec's length
+        // (32 + 1) * 250: blocked_mints limit 250
+        space = 8 + 4 + (32 + 1) * 250,
     )]
-    pub aury_vault: Box<Account<'info, TokenAccount>>,
+    pub mint_blocklist_account: Box<Account<'info, MintBlocklistAccount>>,
 
     #[account(mut)]
-    pub initializer: Signer<'info>,
+    pub admin: Signer<'info>,
 
     pub system_program: Program<'info, System>,
-    pub token_program: Program<'info, Token>,
     pub rent: Sysvar<'info, Rent>,
 }
 
 #[derive(Accounts)]
-#[instruction(_nonce_staking: u8)]
-pub struct FreezeProgram<'info> {
+#[instruction(_nonce_staking: u8, _nonce_mint_blocklist: u8)]
+pub struct RemoveBlockedMints<'info> {
     #[account(
-        mut,
-        seeds = [ constants::STAKING_PDA_SEED.as_ref() ],
+        seeds = [ constants::STAKING_PDA_SEED ],
         bump = _nonce_staking,
     )]
     pub staking_account: Box<Account<'info, StakingAccount>>,
 
+    #[account(
+        mut,
+        seeds = [ constants::MINT_BLOCKLIST_PDA_SEED ],
+        bump = _nonce_mint_blocklist,
+    )]
+    pub mint_blocklist_account: Box<Account<'info, MintBlocklistAccount>>,
+
     pub admin: Signer<'info>,
 }
 
 #[derive(Accounts)]
 #[instruction(_nonce_staking: u8)]
-pub struct UpdateAdmin<'info> {
+pub struct AddReward<'info> {
     #[account(
         mut,
         seeds = [ constants::STAKING_PDA_SEED.as_ref() ],
@@ -887,67 +2959,198 @@ pub struct UpdateAdmin<'info> {
 }
 
 #[derive(Accounts)]
-#[instruction(_nonce_staking: u8)]
-pub struct UpdateAuthorizedCreator<'info> {
+#[instruction(_nonce_staking: u8, _nonce_reward_config: u8)]
+pub struct SetRewardConfig<'info> {
     #[account(
-        mut,
-        seeds = [ constants::STAKING_PDA_SEED.as_ref() ],
+        seeds = [ constants::STAKING_PDA_SEED ],
         bump = _nonce_staking,
     )]
     pub staking_account: Box<Account<'info, StakingAccount>>,
 
+    #[account(constraint = is_token_program(nft_mint.owner))]
+    pub nft_mint: AccountInfo<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = admin,
+        seeds = [ constants::REWARD_CONFIG_PDA_SEED, nft_mint.key().as_ref() ],
+        bump = _nonce_reward_config,
+        // 8: account's signature on the anchor
+        // 32: nft_mint
+        // 1 + 2 + 2: equipment
+        // 8 + 2: consumable
+        space = 8 + 32 + 1 + 2 + 2 + 8 + 2,
+    )]
+    pub reward_config_account: Box<Account<'info, RewardConfigAccount>>,
+
+    #[account(mut)]
     pub admin: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
 }
 
 #[derive(Accounts)]
-#[instruction(_nonce_staking: u8)]
-pub struct UpdateStakingPeriod<'info> {
+#[instruction(_nonce_staking: u8, recipe_id: u32, _nonce_recipe: u8)]
+pub struct SetRecipe<'info> {
     #[account(
-        mut,
-        seeds = [ constants::STAKING_PDA_SEED.as_ref() ],
+        seeds = [ constants::STAKING_PDA_SEED ],
         bump = _nonce_staking,
     )]
     pub staking_account: Box<Account<'info, StakingAccount>>,
 
+    #[account(constraint = is_token_program(output_mint.owner))]
+    pub output_mint: AccountInfo<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = admin,
+        seeds = [ constants::RECIPE_PDA_SEED, recipe_id.to_string().as_ref() ],
+        bump = _nonce_recipe,
+        // 8: account's signature on the anchor
+        // 4: recipe_id
+        // 4: inputs Vec's length
+        // (32 + 8) * 5: inputs limit 5
+        // 32: output_mint
+        // 8: output_amount
+        // 8: output_supply_cap
+        space = 8 + 4 + 4 + (32 + 8) * 5 + 32 + 8 + 8,
+    )]
+    pub recipe_account: Box<Account<'info, RecipeAccount>>,
+
+    #[account(mut)]
     pub admin: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
 }
 
 #[derive(Accounts)]
-#[instruction(_nonce_staking: u8)]
-pub struct AddAuthorizedNameStarts<'info> {
+#[instruction(_nonce_staking: u8, _recipe_id: u32, _nonce_recipe: u8)]
+pub struct RemoveRecipe<'info> {
+    #[account(
+        seeds = [ constants::STAKING_PDA_SEED ],
+        bump = _nonce_staking,
+    )]
+    pub staking_account: Box<Account<'info, StakingAccount>>,
+
     #[account(
         mut,
-        seeds = [ constants::STAKING_PDA_SEED.as_ref() ],
+        close = admin,
+        seeds = [ constants::RECIPE_PDA_SEED, _recipe_id.to_string().as_ref() ],
+        bump = _nonce_recipe,
+    )]
+    pub recipe_account: Box<Account<'info, RecipeAccount>>,
+
+    #[account(mut)]
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(_nonce_staking: u8, destination_id: u32, _nonce_destination: u8)]
+pub struct SetDestination<'info> {
+    #[account(
+        seeds = [ constants::STAKING_PDA_SEED ],
         bump = _nonce_staking,
     )]
     pub staking_account: Box<Account<'info, StakingAccount>>,
 
+    #[account(
+        init_if_needed,
+        payer = admin,
+        seeds = [ constants::DESTINATION_PDA_SEED, destination_id.to_string().as_ref() ],
+        bump = _nonce_destination,
+        // 8: account's signature on the anchor
+        // 4: destination_id
+        // 4 + 32: name max_length 32
+        // 4 + 8 * 5: allowed_durations limit 5
+        // 8: entry_fee
+        // 1 + 1 + 4 + (1 + 4 + 32 + 1 + 1) * 8: party_rules
+        // 4: loot_table_id
+        // 4: capacity
+        // 4: active_positions
+        space = 8 + 4 + 4 + 32 + 4 + 8 * 5 + 8 + 1 + 1 + 4 + (1 + 4 + 32 + 1 + 1) * 8 + 4 + 4 + 4,
+    )]
+    pub destination_account: Box<Account<'info, DestinationAccount>>,
+
+    #[account(mut)]
     pub admin: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
 }
 
 #[derive(Accounts)]
-#[instruction(_nonce_staking: u8)]
-pub struct RemoveAuthorizedNameStarts<'info> {
+#[instruction(_nonce_staking: u8, round_id: u32, _nonce_round: u8)]
+pub struct OpenRound<'info> {
     #[account(
-        mut,
-        seeds = [ constants::STAKING_PDA_SEED.as_ref() ],
+        seeds = [ constants::STAKING_PDA_SEED ],
         bump = _nonce_staking,
     )]
     pub staking_account: Box<Account<'info, StakingAccount>>,
 
+    #[account(
+        init,
+        payer = admin,
+        seeds = [ constants::ROUND_PDA_SEED, round_id.to_string().as_ref() ],
+        bump = _nonce_round,
+        // 8: account's signature on the anchor
+        // 4: round_id
+        // 8: enrollment_start
+        // 8: enrollment_end
+        // 8: departure_at
+        // 8: return_at
+        // 4: enrolled_positions
+        // 1: cancelled
+        space = 8 + 4 + 8 + 8 + 8 + 8 + 4 + 1,
+    )]
+    pub round_account: Box<Account<'info, RoundAccount>>,
+
+    #[account(mut)]
     pub admin: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
 }
 
 #[derive(Accounts)]
-#[instruction(_nonce_staking: u8)]
-pub struct AddReward<'info> {
+#[instruction(_nonce_staking: u8, _round_id: u32, _nonce_round: u8)]
+pub struct CancelRound<'info> {
+    #[account(
+        seeds = [ constants::STAKING_PDA_SEED ],
+        bump = _nonce_staking,
+    )]
+    pub staking_account: Box<Account<'info, StakingAccount>>,
+
     #[account(
         mut,
-        seeds = [ constants::STAKING_PDA_SEED.as_ref() ],
+        seeds = [ constants::ROUND_PDA_SEED, _round_id.to_string().as_ref() ],
+        bump = _nonce_round,
+        constraint = !round_account.cancelled,
+    )]
+    pub round_account: Box<Account<'info, RoundAccount>>,
+
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(_nonce_staking: u8, _destination_id: u32, _nonce_destination: u8)]
+pub struct RemoveDestination<'info> {
+    #[account(
+        seeds = [ constants::STAKING_PDA_SEED ],
         bump = _nonce_staking,
     )]
     pub staking_account: Box<Account<'info, StakingAccount>>,
 
+    #[account(
+        mut,
+        close = admin,
+        seeds = [ constants::DESTINATION_PDA_SEED, _destination_id.to_string().as_ref() ],
+        bump = _nonce_destination,
+    )]
+    pub destination_account: Box<Account<'info, DestinationAccount>>,
+
+    #[account(mut)]
     pub admin: Signer<'info>,
 }
 
@@ -966,11 +3169,12 @@ pub struct RemoveReward<'info> {
 
     pub admin: Signer<'info>,
 
-    pub token_program: Program<'info, Token>,
+    #[account(constraint = is_token_program(token_program.key))]
+    pub token_program: AccountInfo<'info>,
 }
 
 #[derive(Accounts)]
-#[instruction(_nonce_staking: u8)]
+#[instruction(_nonce_staking: u8, _nonce_mint_blocklist: u8)]
 pub struct AddWinner<'info> {
     #[account(
         mut,
@@ -979,11 +3183,17 @@ pub struct AddWinner<'info> {
     )]
     pub staking_account: Box<Account<'info, StakingAccount>>,
 
+    #[account(
+        seeds = [ constants::MINT_BLOCKLIST_PDA_SEED ],
+        bump = _nonce_mint_blocklist,
+    )]
+    pub mint_blocklist_account: Box<Account<'info, MintBlocklistAccount>>,
+
     pub admin: Signer<'info>,
 }
 
 #[derive(Accounts)]
-#[instruction(_nonce_staking: u8, _nonce_aury_vault: u8)]
+#[instruction(_nonce_staking: u8, _nonce_aury_vault: u8, _nonce_mint_blocklist: u8)]
 pub struct AddAuryWinner<'info> {
     #[account(
         mut,
@@ -994,67 +3204,126 @@ pub struct AddAuryWinner<'info> {
 
     #[account(
         address = constants::AURY_TOKEN_MINT_PUBKEY.parse::<Pubkey>().unwrap(),
+        constraint = aury_mint.owner == token_program.key,
     )]
-    pub aury_mint: Box<Account<'info, Mint>>,
+    pub aury_mint: AccountInfo<'info>,
 
     #[account(
         mut,
         seeds = [ aury_mint.key().as_ref() ],
         bump = _nonce_aury_vault,
     )]
-    pub aury_vault: Box<Account<'info, TokenAccount>>,
+    pub aury_vault: AccountInfo<'info>,
 
     #[account(mut)]
-    pub aury_from: Box<Account<'info, TokenAccount>>,
+    pub aury_from: AccountInfo<'info>,
+
+    #[account(
+        seeds = [ constants::MINT_BLOCKLIST_PDA_SEED ],
+        bump = _nonce_mint_blocklist,
+    )]
+    pub mint_blocklist_account: Box<Account<'info, MintBlocklistAccount>>,
 
     pub admin: Signer<'info>,
 
-    pub token_program: Program<'info, Token>,
+    #[account(constraint = is_token_program(token_program.key))]
+    pub token_program: AccountInfo<'info>,
 }
 
 #[derive(Accounts)]
-#[instruction[nonce_nft_vault: Vec<u8>, _nonce_staking: u8, _nonce_user_staking_counter: u8, _nonce_user_staking: u8]]
+#[instruction[nonce_nft_vault: Vec<u8>, nonce_staking: u8, _nonce_user_staking_counter: u8, _nonce_user_staking: u8, _nonce_eligibility_rules: u8, _nonce_mint_blocklist: u8]]
 pub struct Stake<'info> {
     pub nft_from_authority: Signer<'info>,
 
     #[account(
-        mut,
-        seeds = [ constants::STAKING_PDA_SEED.as_ref() ],
+        mut,
+        seeds = [ constants::STAKING_PDA_SEED.as_ref() ],
+        bump = nonce_staking,
+        constraint = !staking_account.freeze_program,
+    )]
+    pub staking_account: Box<Account<'info, StakingAccount>>,
+
+    #[account(
+        init_if_needed,
+        payer = nft_from_authority,
+        seeds = [ nft_from_authority.key().as_ref() ],
+        bump = _nonce_user_staking_counter,
+    )]
+    pub user_staking_counter_account: Box<Account<'info, UserStakingCounterAccount>>,
+
+    #[account(
+        init_if_needed,
+        payer = nft_from_authority,
+        seeds = [ user_staking_counter_account.counter.to_string().as_ref(), nft_from_authority.key().as_ref() ],
+        bump = _nonce_user_staking,
+        space = USER_STAKING_SPACE,
+    )]
+    pub user_staking_account: Box<Account<'info, UserStakingAccount>>,
+
+    // not deserialized, created on the wallet's first lock_stake
+    #[account(
+        seeds = [ constants::WALLET_POSITIONS_PDA_SEED, nft_from_authority.key().as_ref() ],
+        bump,
+    )]
+    pub wallet_positions_account: AccountInfo<'info>,
+
+    #[account(
+        seeds = [ constants::ELIGIBILITY_RULES_PDA_SEED ],
+        bump = _nonce_eligibility_rules,
+    )]
+    pub eligibility_rules_account: Box<Account<'info, EligibilityRulesAccount>>,
+
+    #[account(
+        seeds = [ constants::MINT_BLOCKLIST_PDA_SEED ],
+        bump = _nonce_mint_blocklist,
+    )]
+    pub mint_blocklist_account: Box<Account<'info, MintBlocklistAccount>>,
+
+    #[account(address = mpl_token_metadata::id())]
+    pub token_metadata_program: AccountInfo<'info>,
+
+    pub system_program: Program<'info, System>,
+    #[account(constraint = is_token_program(token_program.key))]
+    pub token_program: AccountInfo<'info>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+#[instruction(nonce_item_vault: Vec<u8>, amounts: Vec<u64>, _nonce_staking: u8, _nonce_user_staking_counter: u8, _nonce_user_staking: u8)]
+pub struct StakeItems<'info> {
+    #[account(mut)]
+    pub nft_from_authority: Signer<'info>,
+
+    #[account(
+        seeds = [ constants::STAKING_PDA_SEED ],
         bump = _nonce_staking,
         constraint = !staking_account.freeze_program,
     )]
     pub staking_account: Box<Account<'info, StakingAccount>>,
 
     #[account(
-        init_if_needed,
-        payer = nft_from_authority,
         seeds = [ nft_from_authority.key().as_ref() ],
         bump = _nonce_user_staking_counter,
     )]
     pub user_staking_counter_account: Box<Account<'info, UserStakingCounterAccount>>,
 
     #[account(
-        init_if_needed,
-        payer = nft_from_authority,
+        mut,
         seeds = [ user_staking_counter_account.counter.to_string().as_ref(), nft_from_authority.key().as_ref() ],
         bump = _nonce_user_staking,
-        // 8: account's signature on the anchor
-        // 4: index
-        // 32: wallet
-        // 4: nft_mint_keys Vec's length
-        // 32 * 10: nft_mint_keys limit 10
-        // 4: claimable Vec's length
-        // (32 + 2) * 5: claimable limit 5
-        // 8: staking_at
-        // 8: staking_period
-        // 8: claimable aury amount
-        // 8: aury_deposit
-        space = 8 + 4 + 32 + 4 + 32 * 10 + 4 + (32 + 2) * 5 + 8 + 8 + 8 + 8, 
     )]
     pub user_staking_account: Box<Account<'info, UserStakingAccount>>,
 
+    // not deserialized, created on the wallet's first lock_stake
+    #[account(
+        seeds = [ constants::WALLET_POSITIONS_PDA_SEED, nft_from_authority.key().as_ref() ],
+        bump,
+    )]
+    pub wallet_positions_account: AccountInfo<'info>,
+
     pub system_program: Program<'info, System>,
-    pub token_program: Program<'info, Token>,
+    #[account(constraint = is_token_program(token_program.key))]
+    pub token_program: AccountInfo<'info>,
     pub rent: Sysvar<'info, Rent>,
 }
 
@@ -1085,22 +3354,93 @@ pub struct LockStake<'info> {
         bump = _nonce_user_staking,
     )]
     pub user_staking_account: Box<Account<'info, UserStakingAccount>>,
+
+    // not deserialized, created here on the wallet's first lock_stake
+    #[account(
+        mut,
+        seeds = [ constants::WALLET_POSITIONS_PDA_SEED, nft_from_authority.key().as_ref() ],
+        bump,
+    )]
+    pub wallet_positions_account: AccountInfo<'info>,
+
+    #[account(
+        seeds = [ constants::PARTY_RULES_PDA_SEED ],
+        bump,
+    )]
+    pub party_rules_account: Box<Account<'info, PartyRulesAccount>>,
+
+    #[account(
+        seeds = [ constants::SYNERGIES_PDA_SEED ],
+        bump,
+    )]
+    pub synergies_account: Box<Account<'info, SynergiesAccount>>,
+
+    #[account(
+        mut,
+        seeds = [ constants::DESTINATION_PDA_SEED, destination_account.destination_id.to_string().as_ref() ],
+        bump,
+    )]
+    pub destination_account: Box<Account<'info, DestinationAccount>>,
+
+    #[account(
+        address = constants::AURY_TOKEN_MINT_PUBKEY.parse::<Pubkey>().unwrap(),
+        constraint = aury_mint.owner == token_program.key,
+    )]
+    pub aury_mint: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds = [ aury_mint.key().as_ref() ],
+        bump = _nonce_aury_vault,
+    )]
+    pub aury_vault: AccountInfo<'info>,
+
+    #[account(mut)]
+    pub aury_from: AccountInfo<'info>,
+
+    pub system_program: Program<'info, System>,
+    #[account(constraint = is_token_program(token_program.key))]
+    pub token_program: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(_nonce_staking: u8, _user_staking_index: u32, _nonce_user_staking: u8, _nonce_aury_vault: u8)]
+pub struct ExtendExpedition<'info> {
+    pub nft_from_authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [ constants::STAKING_PDA_SEED ],
+        bump = _nonce_staking,
+        constraint = !staking_account.freeze_program,
+    )]
+    pub staking_account: Box<Account<'info, StakingAccount>>,
+
+    #[account(
+        mut,
+        seeds = [ _user_staking_index.to_string().as_ref(), nft_from_authority.key().as_ref() ],
+        bump = _nonce_user_staking,
+    )]
+    pub user_staking_account: Box<Account<'info, UserStakingAccount>>,
+
     #[account(
         address = constants::AURY_TOKEN_MINT_PUBKEY.parse::<Pubkey>().unwrap(),
+        constraint = aury_mint.owner == token_program.key,
     )]
-    pub aury_mint: Box<Account<'info, Mint>>,
+    pub aury_mint: AccountInfo<'info>,
 
     #[account(
         mut,
         seeds = [ aury_mint.key().as_ref() ],
         bump = _nonce_aury_vault,
     )]
-    pub aury_vault: Box<Account<'info, TokenAccount>>,
+    pub aury_vault: AccountInfo<'info>,
 
     #[account(mut)]
-    pub aury_from: Box<Account<'info, TokenAccount>>,
+    pub aury_from: AccountInfo<'info>,
 
-    pub token_program: Program<'info, Token>,
+    #[account(constraint = is_token_program(token_program.key))]
+    pub token_program: AccountInfo<'info>,
 }
 
 #[derive(Accounts)]
@@ -1124,7 +3464,21 @@ pub struct Unstake<'info> {
     )]
     pub user_staking_account: Box<Account<'info, UserStakingAccount>>,
 
-    pub token_program: Program<'info, Token>,
+    // not deserialized, positions locked before the destinations existed have none
+    #[account(mut)]
+    pub destination_account: AccountInfo<'info>,
+
+    // not deserialized, positions locked before the wallet positions existed aren't counted
+    #[account(mut)]
+    pub wallet_positions_account: AccountInfo<'info>,
+
+    #[account(address = mpl_token_metadata::id())]
+    pub token_metadata_program: AccountInfo<'info>,
+
+    pub system_program: Program<'info, System>,
+
+    #[account(constraint = is_token_program(token_program.key))]
+    pub token_program: AccountInfo<'info>,
 }
 
 #[derive(Accounts)]
@@ -1133,6 +3487,12 @@ pub struct Close<'info> {
     #[account(mut)]
     pub nft_to_authority: Signer<'info>,
 
+    #[account(
+        seeds = [ constants::STAKING_PDA_SEED ],
+        bump,
+    )]
+    pub staking_account: Box<Account<'info, StakingAccount>>,
+
     #[account(
         mut,
         close = nft_to_authority,
@@ -1142,6 +3502,42 @@ pub struct Close<'info> {
     pub user_staking_account: Box<Account<'info, UserStakingAccount>>,
 }
 
+#[derive(Accounts)]
+#[instruction(_nonce_staking: u8)]
+pub struct MigrateStaking<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    // not deserialized, the account may still have the old layout
+    #[account(
+        mut,
+        owner = id(),
+        seeds = [ constants::STAKING_PDA_SEED ],
+        bump = _nonce_staking,
+    )]
+    pub staking_account: AccountInfo<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(_user_staking_index: u32, _nonce_user_staking: u8)]
+pub struct MigrateUserStaking<'info> {
+    #[account(mut)]
+    pub nft_from_authority: Signer<'info>,
+
+    // not deserialized, the account may still have the old layout
+    #[account(
+        mut,
+        owner = id(),
+        seeds = [ _user_staking_index.to_string().as_ref(), nft_from_authority.key().as_ref() ],
+        bump = _nonce_user_staking,
+    )]
+    pub user_staking_account: AccountInfo<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
 #[derive(Accounts)]
 #[instruction(nonce_staking: u8, _user_staking_index: u32, _nonce_user_staking: u8)]
 pub struct Claim<'info> {
@@ -1162,7 +3558,8 @@ pub struct Claim<'info> {
     )]
     pub user_staking_account: Box<Account<'info, UserStakingAccount>>,
 
-    pub token_program: Program<'info, Token>,
+    #[account(constraint = is_token_program(token_program.key))]
+    pub token_program: AccountInfo<'info>,
 }
 
 #[derive(Accounts)]
@@ -1170,21 +3567,29 @@ pub struct Claim<'info> {
 pub struct ClaimAuryReward<'info> {
     #[account(
         address = constants::AURY_TOKEN_MINT_PUBKEY.parse::<Pubkey>().unwrap(),
+        constraint = aury_mint.owner == token_program.key,
     )]
-    pub aury_mint: Box<Account<'info, Mint>>,
+    pub aury_mint: AccountInfo<'info>,
 
     #[account(
         mut,
         seeds = [ aury_mint.key().as_ref() ],
         bump = nonce_aury_vault,
     )]
-    pub aury_vault: Box<Account<'info, TokenAccount>>,
+    pub aury_vault: AccountInfo<'info>,
 
     #[account(mut)]
-    pub aury_to: Box<Account<'info, TokenAccount>>,
+    pub aury_to: AccountInfo<'info>,
 
     pub aury_to_authority: Signer<'info>,
 
+    #[account(
+        mut,
+        seeds = [ constants::STAKING_PDA_SEED ],
+        bump,
+    )]
+    pub staking_account: Box<Account<'info, StakingAccount>>,
+
     #[account(
         mut,
         seeds = [ _user_staking_index.to_string().as_ref(), aury_to_authority.key().as_ref() ],
@@ -1192,7 +3597,87 @@ pub struct ClaimAuryReward<'info> {
     )]
     pub user_staking_account: Box<Account<'info, UserStakingAccount>>,
 
-    pub token_program: Program<'info, Token>,
+    #[account(
+        seeds = [ constants::MINT_BLOCKLIST_PDA_SEED ],
+        bump,
+    )]
+    pub mint_blocklist_account: Box<Account<'info, MintBlocklistAccount>>,
+
+    #[account(constraint = is_token_program(token_program.key))]
+    pub token_program: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(_nonce_staking: u8, _user_staking_index: u32, _nonce_user_staking: u8, _nonce_reward_config: u8)]
+pub struct UseBoost<'info> {
+    pub boost_from_authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [ constants::STAKING_PDA_SEED ],
+        bump = _nonce_staking,
+        constraint = !staking_account.freeze_program,
+    )]
+    pub staking_account: Box<Account<'info, StakingAccount>>,
+
+    #[account(
+        mut,
+        seeds = [ _user_staking_index.to_string().as_ref(), boost_from_authority.key().as_ref() ],
+        bump = _nonce_user_staking,
+    )]
+    pub user_staking_account: Box<Account<'info, UserStakingAccount>>,
+
+    #[account(mut)]
+    pub boost_mint: AccountInfo<'info>,
+
+    #[account(
+        seeds = [ constants::REWARD_CONFIG_PDA_SEED, boost_mint.key().as_ref() ],
+        bump = _nonce_reward_config,
+    )]
+    pub reward_config_account: Box<Account<'info, RewardConfigAccount>>,
+
+    #[account(
+        seeds = [ constants::MINT_BLOCKLIST_PDA_SEED ],
+        bump,
+    )]
+    pub mint_blocklist_account: Box<Account<'info, MintBlocklistAccount>>,
+
+    #[account(mut)]
+    pub boost_from: AccountInfo<'info>,
+
+    #[account(constraint = is_token_program(token_program.key))]
+    pub token_program: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(nonce_staking: u8, _recipe_id: u32, _nonce_recipe: u8)]
+pub struct Craft<'info> {
+    pub crafter: Signer<'info>,
+
+    #[account(
+        seeds = [ constants::STAKING_PDA_SEED ],
+        bump = nonce_staking,
+        constraint = !staking_account.freeze_program,
+    )]
+    pub staking_account: Box<Account<'info, StakingAccount>>,
+
+    #[account(
+        seeds = [ constants::RECIPE_PDA_SEED, _recipe_id.to_string().as_ref() ],
+        bump = _nonce_recipe,
+    )]
+    pub recipe_account: Box<Account<'info, RecipeAccount>>,
+
+    #[account(
+        mut,
+        address = recipe_account.output_mint,
+    )]
+    pub output_mint: AccountInfo<'info>,
+
+    #[account(mut)]
+    pub output_to: AccountInfo<'info>,
+
+    #[account(constraint = is_token_program(token_program.key))]
+    pub token_program: AccountInfo<'info>,
 }
 
 #[derive(Accounts)]
@@ -1202,7 +3687,7 @@ pub struct MintTo<'info> {
     pub nft_mint: UncheckedAccount<'info>,
 
     #[account(mut)]
-    pub nft_to: Box<Account<'info, TokenAccount>>,
+    pub nft_to: AccountInfo<'info>,
 
     #[account(
         mut,
@@ -1214,7 +3699,8 @@ pub struct MintTo<'info> {
 
     pub admin: Signer<'info>,
 
-    pub token_program: Program<'info, Token>,
+    #[account(constraint = is_token_program(token_program.key))]
+    pub token_program: AccountInfo<'info>,
 }
 
 #[account]
@@ -1227,6 +3713,220 @@ pub struct StakingAccount {
     pub minimum_staking_period: u64,
     pub maximum_staking_period: u64,
     pub active_rewards: Vec<Pubkey>,
+    pub aury_emission_rates: Vec<AuryEmissionRate>,
+    pub aury_emission_pool: u64,
+    pub minimum_rarity: u8,
+    // rarity multipliers in basis points, indexed by rarity
+    pub rarity_multipliers: Vec<u16>,
+    // verified metaplex collections accepted regardless of creator and name
+    pub authorized_collections: Vec<Pubkey>,
+    // verified creators accepted alongside the authorized_creator
+    pub authorized_creators: Vec<Pubkey>,
+    // seconds an aurorian rests after an expedition before it can be staked again
+    pub expedition_cooldown: u64,
+    // locked positions and their aurorians, a cap of 0 is unlimited
+    pub active_positions: u32,
+    pub active_nfts: u32,
+    pub max_active_positions: u32,
+    pub max_active_nfts: u32,
+    // locked positions a single wallet can have at once, 0 is unlimited
+    pub max_wallet_positions: u32,
+}
+
+// Aury emitted per second for each staked nft, applied from the given staking period
+#[derive(AnchorSerialize, AnchorDeserialize, Copy, Clone, Default)]
+pub struct AuryEmissionRate {
+    pub minimum_staking_period: u64,
+    pub rate: u64,
+}
+
+// Rule tree node, composite nodes reference child nodes by their index in the rules
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub enum EligibilityRule {
+    All { indexes: Vec<u8> },
+    Any { indexes: Vec<u8> },
+    Not { index: u8 },
+    VerifiedCreator { creators: Vec<Pubkey> },
+    VerifiedCollection { collections: Vec<Pubkey> },
+    Symbol { symbol: String },
+    UpdateAuthority { update_authority: Pubkey },
+    Name { name: String },
+    NameStartsWith { prefix: String },
+    NameEndsWith { suffix: String },
+    MasterEdition,
+    Edition,
+    MintAllowList { mints: Vec<Pubkey> },
+    MintDenyList { mints: Vec<Pubkey> },
+    MinimumLevel { level: u16 },
+}
+
+// Selects the party members a constraint counts
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub enum PartyMember {
+    NamePrefix { prefix: String },
+    Traits { traits: u32 },
+    MinimumRarity { rarity: u8 },
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct PartyConstraint {
+    pub member: PartyMember,
+    pub min: u8,
+    pub max: u8,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default)]
+pub struct PartyRules {
+    pub min_party_size: u8,
+    pub max_party_size: u8,
+    pub constraints: Vec<PartyConstraint>,
+}
+
+#[account]
+#[derive(Default)]
+pub struct PartyRulesAccount {
+    pub party_rules: PartyRules,
+}
+
+// An allowed_durations or capacity left empty doesn't restrict the expedition
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default)]
+pub struct DestinationConfig {
+    pub name: String,
+    pub allowed_durations: Vec<u64>,
+    pub entry_fee: u64,
+    pub party_rules: PartyRules,
+    // identifies the loot table of the off-chain draw
+    pub loot_table_id: u32,
+    pub capacity: u32,
+}
+
+#[account]
+#[derive(Default)]
+pub struct DestinationAccount {
+    pub destination_id: u32,
+    pub config: DestinationConfig,
+    pub active_positions: u32,
+}
+
+// Positions enrolled between enrollment_start and enrollment_end depart and return together
+#[account]
+#[derive(Default)]
+pub struct RoundAccount {
+    pub round_id: u32,
+    pub enrollment_start: u64,
+    pub enrollment_end: u64,
+    pub departure_at: u64,
+    pub return_at: u64,
+    pub enrolled_positions: u32,
+    // enrolled positions can unstake at once, with their deposit refunded
+    pub cancelled: bool,
+}
+
+// A party with at least count matching members gets the bonus, in basis points
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct Synergy {
+    pub member: PartyMember,
+    pub count: u8,
+    pub bonus: u16,
+}
+
+#[account]
+#[derive(Default)]
+pub struct SynergiesAccount {
+    pub synergies: Vec<Synergy>,
+}
+
+// The root of the rule tree is the first rule, no rules falls back to the authorized creator and name starts
+#[account]
+#[derive(Default)]
+pub struct EligibilityRulesAccount {
+    pub rules: Vec<EligibilityRule>,
+}
+
+// Slot 0 means the reward can't be equipped, boosts are in basis points
+#[derive(AnchorSerialize, AnchorDeserialize, Copy, Clone, Default)]
+pub struct EquipmentConfig {
+    pub slot: u8,
+    pub aury_boost: u16,
+    pub odds_boost: u16,
+}
+
+// Effects of burning one token, the reward multiplier is in basis points
+#[derive(AnchorSerialize, AnchorDeserialize, Copy, Clone, Default)]
+pub struct ConsumableConfig {
+    pub lock_reduction: u64,
+    pub reward_multiplier: u16,
+}
+
+#[account]
+#[derive(Default)]
+pub struct RewardConfigAccount {
+    pub nft_mint: Pubkey,
+    pub equipment: EquipmentConfig,
+    pub consumable: ConsumableConfig,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Copy, Clone, Default)]
+pub struct RecipeInput {
+    pub nft_mint: Pubkey,
+    pub amount: u64,
+}
+
+// A supply cap of 0 leaves the output uncapped
+#[account]
+#[derive(Default)]
+pub struct RecipeAccount {
+    pub recipe_id: u32,
+    pub inputs: Vec<RecipeInput>,
+    pub output_mint: Pubkey,
+    pub output_amount: u64,
+    pub output_supply_cap: u64,
+}
+
+// Reason codes are assigned off-chain, e.g. 1: reported stolen
+#[derive(AnchorSerialize, AnchorDeserialize, Copy, Clone, Default)]
+pub struct BlockedMint {
+    pub nft_mint: Pubkey,
+    pub reason: u8,
+}
+
+#[account]
+#[derive(Default)]
+pub struct MintBlocklistAccount {
+    pub blocked_mints: Vec<BlockedMint>,
+}
+
+#[account]
+#[derive(Default)]
+pub struct AurorianRegistryAccount {
+    pub nft_mint: Pubkey,
+    pub rarity: u8,
+    pub traits: u32,
+}
+
+// Updated by the program on every unstake, other programs can read it from its pda
+#[account]
+#[derive(Default)]
+pub struct AurorianStatsAccount {
+    pub nft_mint: Pubkey,
+    pub expeditions_completed: u32,
+    pub total_locked_time: u64,
+    pub xp: u64,
+    pub level: u16,
+    // the aurorian can't be staked before this timestamp
+    pub cooldown_until: u64,
+}
+
+// Exists while the nft is staked, closed on unstake
+#[account]
+#[derive(Default)]
+pub struct StakeRecordAccount {
+    pub nft_mint: Pubkey,
+    pub wallet: Pubkey,
+    pub user_staking_index: u32,
+    // the owner's token account for non-custodial stakes
+    pub vault: Pubkey,
+    pub staked_at: u64,
 }
 
 #[account]
@@ -1235,12 +3935,60 @@ pub struct UserStakingCounterAccount {
     pub counter: u32,
 }
 
+// Locked positions of a wallet that haven't fully returned yet, created on its first lock_stake
+#[account]
+#[derive(Default)]
+pub struct WalletPositionsAccount {
+    pub wallet: Pubkey,
+    pub active_positions: u32,
+}
+
 #[derive(AnchorSerialize, AnchorDeserialize, Copy, Clone, Default)]
 pub struct ClaimableToken {
     pub nft_mint: Pubkey,
     pub amount: u16,
 }
 
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default)]
+pub struct StakedNft {
+    pub nft_mint: Pubkey,
+    pub rarity: u8,
+    pub traits: u32,
+    // frozen in the owner's token account instead of transferred to a vault
+    pub non_custodial: bool,
+    // metadata snapshot at stake time
+    pub name: String,
+    pub symbol: String,
+    pub primary_creator: Pubkey,
+    pub collection: Option<Pubkey>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Copy, Clone, Default)]
+pub struct StakedItem {
+    pub nft_mint: Pubkey,
+    pub amount: u64,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Copy, Clone, Default)]
+pub struct Equipment {
+    pub nft_mint: Pubkey,
+    pub slot: u8,
+}
+
+// The staking and destination terms in effect at lock_stake
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default)]
+pub struct PositionConfig {
+    pub version: u8,
+    pub minimum_staking_period: u64,
+    pub maximum_staking_period: u64,
+    pub allowed_durations: Vec<u64>,
+    pub rarity_multipliers: Vec<u16>,
+    pub aury_emission_rates: Vec<AuryEmissionRate>,
+    pub expedition_cooldown: u64,
+    pub entry_fee: u64,
+    pub loot_table_id: u32,
+}
+
 #[account]
 #[derive(Default)]
 pub struct UserStakingAccount {
@@ -1252,6 +4000,22 @@ pub struct UserStakingAccount {
     pub staking_period: u64,
     pub claimable_aury_amount: u64,
     pub aury_deposit: u64,
+    pub aury_emission_rate: u64,
+    pub last_aury_accrual_at: u64,
+    // the data recorded for each nft_mint_keys entry, missing for nfts staked before the registry
+    pub staked_nfts: Vec<StakedNft>,
+    pub staked_items: Vec<StakedItem>,
+    pub equipment: Vec<Equipment>,
+    // basis points added to the aury emission rate
+    pub aury_boost: u64,
+    // basis points added to the odds, read by the off-chain draw
+    pub odds_boost: u64,
+    // basis points applied to the aury emission rate by the matched synergies
+    pub synergy_multiplier: u64,
+    pub destination_id: u32,
+    // the round the position is enrolled in, if any
+    pub round_id: Option<u32>,
+    pub config: PositionConfig,
 }
 
 #[error]
@@ -1303,7 +4067,97 @@ pub enum ErrorCode {
     #[msg("DifferentIndex.")]
     DifferentIndex, // 6022, 0x1784 
     #[msg("DifferentWallet")]
-    DifferentWallet // 6023, 0x1784 
+    DifferentWallet, // 6023, 0x1784 
+    #[msg("Invalid aury emission rates")]
+    InvalidAuryEmissionRates, // 6024, 0x1788
+    #[msg("Insufficient aury emission pool")]
+    InsufficientAuryEmissionPool, // 6025, 0x1789
+    #[msg("Numerical overflow")]
+    NumericalOverflow, // 6026, 0x178a
+    #[msg("Invalid rarity multipliers")]
+    InvalidRarityMultipliers, // 6027, 0x178b
+    #[msg("Invalid aurorian registry")]
+    InvalidAurorianRegistry, // 6028, 0x178c
+    #[msg("Rarity too low")]
+    RarityTooLow, // 6029, 0x178d
+    #[msg("Too many authorized collections")]
+    TooManyAuthorizedCollections, // 6030, 0x178e
+    #[msg("Invalid eligibility rules")]
+    InvalidEligibilityRules, // 6031, 0x178f
+    #[msg("Nft doesn't match the eligibility rules")]
+    NotEligible, // 6032, 0x1790
+    #[msg("Too many authorized creators")]
+    TooManyAuthorizedCreators, // 6033, 0x1791
+    #[msg("Too many blocked mints")]
+    TooManyBlockedMints, // 6034, 0x1792
+    #[msg("Mint is blocked")]
+    MintBlocked, // 6035, 0x1793
+    #[msg("Approve failed")]
+    ApproveFailed, // 6036, 0x1794
+    #[msg("Revoke failed")]
+    RevokeFailed, // 6037, 0x1795
+    #[msg("Freeze account failed")]
+    FreezeAccountFailed, // 6038, 0x1796
+    #[msg("Thaw account failed")]
+    ThawAccountFailed, // 6039, 0x1797
+    #[msg("Invalid token program")]
+    InvalidTokenProgram, // 6040, 0x1798
+    #[msg("Too many staked items")]
+    TooManyStakedItems, // 6041, 0x1799
+    #[msg("Invalid item amount")]
+    InvalidItemAmount, // 6042, 0x179a
+    #[msg("Not equipment")]
+    NotEquipment, // 6043, 0x179b
+    #[msg("Equipment slot taken")]
+    EquipmentSlotTaken, // 6044, 0x179c
+    #[msg("Too many equipment")]
+    TooManyEquipment, // 6045, 0x179d
+    #[msg("Not consumable")]
+    NotConsumable, // 6046, 0x179e
+    #[msg("Expedition finished")]
+    ExpeditionFinished, // 6047, 0x179f
+    #[msg("Burn failed")]
+    BurnFailed, // 6048, 0x17a0
+    #[msg("Invalid recipe")]
+    InvalidRecipe, // 6049, 0x17a1
+    #[msg("Supply cap exceeded")]
+    SupplyCapExceeded, // 6050, 0x17a2
+    #[msg("Invalid aurorian stats")]
+    InvalidAurorianStats, // 6051, 0x17a3
+    #[msg("Aurorian in cooldown")]
+    AurorianInCooldown, // 6052, 0x17a4
+    #[msg("Nft already staked")]
+    NftAlreadyStaked, // 6053, 0x17a5
+    #[msg("Invalid stake record")]
+    InvalidStakeRecord, // 6054, 0x17a6
+    #[msg("Invalid party rules")]
+    InvalidPartyRules, // 6055, 0x17a7
+    #[msg("Invalid party size")]
+    InvalidPartySize, // 6056, 0x17a8
+    #[msg("Party composition not allowed")]
+    InvalidPartyComposition, // 6057, 0x17a9
+    #[msg("Invalid synergies")]
+    InvalidSynergies, // 6058, 0x17aa
+    #[msg("Invalid destination")]
+    InvalidDestination, // 6059, 0x17ab
+    #[msg("Destination full")]
+    DestinationFull, // 6060, 0x17ac
+    #[msg("Destination in use")]
+    DestinationInUse, // 6061, 0x17ad
+    #[msg("Expedition full")]
+    ExpeditionFull, // 6062, 0x17ae
+    #[msg("Too many expeditions for the wallet")]
+    WalletExpeditionsFull, // 6063, 0x17af
+    #[msg("Invalid round")]
+    InvalidRound, // 6064, 0x17b0
+    #[msg("Round isn't open for enrollment")]
+    RoundNotOpen, // 6065, 0x17b1
+    #[msg("Round positions return with the round")]
+    RoundReturnFixed, // 6066, 0x17b2
+    #[msg("Can't close before claim all rewards")]
+    CantCloseBeforeClaim, // 6067, 0x17b3
+    #[msg("Non-custodial staking is only supported for SPL Token mints")]
+    NonCustodialTokenProgramUnsupported, // 6068, 0x17b4
 }
 
 // Asserts the signer is admin
@@ -1317,3 +4171,17 @@ fn is_admin<'info>(
 
     Ok(())
 }
+
+// Asserts the signer is admin of a staking account that may still have the old layout
+fn is_migration_admin<'info>(
+    staking_account: &AccountInfo<'info>,
+    signer: &Signer<'info>,
+) -> Result<()> {
+    // the admin key follows the discriminator in every layout
+    let data = staking_account.try_borrow_data()?;
+    if data.len() < 40 || Pubkey::new(&data[8..40]) != *signer.key {
+        return Err(ErrorCode::NotAdmin.into());
+    }
+
+    Ok(())
+}
@@ -1,11 +1,15 @@
 use anchor_lang::{
     prelude::*,
-    solana_program::program_pack::Pack, __private::CLOSED_ACCOUNT_DISCRIMINATOR,
+    __private::CLOSED_ACCOUNT_DISCRIMINATOR,
     __private::ErrorCode::AccountDidNotSerialize
 };
 use std::io::Write;
-use metaplex_token_metadata::state::Metadata;
-use spl_token::instruction::AuthorityType;
+use mpl_token_metadata::state::{Key, Metadata, TokenMetadataAccount, EDITION, PREFIX};
+use spl_token_2022::{
+    extension::{ExtensionType, StateWithExtensions},
+    instruction::AuthorityType,
+    state::{Account as SplTokenAccount, Mint as SplMint},
+};
 use {
     crate::*,
     anchor_lang::{
@@ -25,6 +29,8 @@ pub struct TokenTransferParams<'a: 'b, 'b> {
     pub source: AccountInfo<'a>,
     /// destination
     pub destination: AccountInfo<'a>,
+    /// mint
+    pub mint: AccountInfo<'a>,
     /// amount
     pub amount: u64,
     /// authority
@@ -101,26 +107,128 @@ pub struct CloseAccountParams<'a: 'b, 'b> {
     pub token_program: AccountInfo<'a>,
 }
 
+///TokenBurnParams
+pub struct TokenBurnParams<'a> {
+    /// mint
+    pub mint: AccountInfo<'a>,
+    /// source
+    pub source: AccountInfo<'a>,
+    /// amount
+    pub amount: u64,
+    /// authority
+    pub authority: AccountInfo<'a>,
+    /// token_program
+    pub token_program: AccountInfo<'a>,
+}
+
+///AurorianStatsParams
+pub struct AurorianStatsParams<'a, 'b> {
+    /// aurorian_stats
+    pub aurorian_stats: &'b AurorianStatsAccount,
+    /// aurorian_stats_info
+    pub aurorian_stats_info: AccountInfo<'a>,
+    /// payer
+    pub payer: AccountInfo<'a>,
+    /// system_program
+    pub system_program: AccountInfo<'a>,
+    /// program_id
+    pub program_id: &'b Pubkey,
+}
+
+///WalletPositionsParams
+pub struct WalletPositionsParams<'a, 'b> {
+    /// wallet_positions
+    pub wallet_positions: &'b WalletPositionsAccount,
+    /// wallet_positions_info
+    pub wallet_positions_info: AccountInfo<'a>,
+    /// payer
+    pub payer: AccountInfo<'a>,
+    /// system_program
+    pub system_program: AccountInfo<'a>,
+    /// program_id
+    pub program_id: &'b Pubkey,
+}
+
+///StakeRecordParams
+pub struct StakeRecordParams<'a, 'b> {
+    /// stake_record
+    pub stake_record: StakeRecordAccount,
+    /// stake_record_info
+    pub stake_record_info: AccountInfo<'a>,
+    /// payer
+    pub payer: AccountInfo<'a>,
+    /// system_program
+    pub system_program: AccountInfo<'a>,
+    /// program_id
+    pub program_id: &'b Pubkey,
+}
+
+///ApproveParams
+pub struct ApproveParams<'a> {
+    /// source
+    pub source: AccountInfo<'a>,
+    /// delegate
+    pub delegate: AccountInfo<'a>,
+    /// owner
+    pub owner: AccountInfo<'a>,
+    /// amount
+    pub amount: u64,
+    /// token_program
+    pub token_program: AccountInfo<'a>,
+}
+
+///RevokeParams
+pub struct RevokeParams<'a> {
+    /// source
+    pub source: AccountInfo<'a>,
+    /// owner
+    pub owner: AccountInfo<'a>,
+    /// token_program
+    pub token_program: AccountInfo<'a>,
+}
+
+///DelegatedAccountParams
+pub struct DelegatedAccountParams<'a: 'b, 'b> {
+    /// delegate
+    pub delegate: AccountInfo<'a>,
+    /// delegate_signer_seeds
+    pub delegate_signer_seeds: &'b [&'b [u8]],
+    /// token_account
+    pub token_account: AccountInfo<'a>,
+    /// edition
+    pub edition: AccountInfo<'a>,
+    /// mint
+    pub mint: AccountInfo<'a>,
+    /// token_program
+    pub token_program: AccountInfo<'a>,
+    /// token_metadata_program
+    pub token_metadata_program: AccountInfo<'a>,
+}
+
 pub fn spl_token_transfer(params: TokenTransferParams<'_, '_>) -> ProgramResult {
     let TokenTransferParams {
         source,
         destination,
+        mint,
         authority,
         token_program,
         amount,
         authority_signer_seeds,
     } = params;
 
+    let decimals = unpack_mint(&mint)?.decimals;
     let result = invoke_signed(
-        &spl_token::instruction::transfer(
+        &spl_token_2022::instruction::transfer_checked(
             token_program.key,
             source.key,
+            mint.key,
             destination.key,
             authority.key,
             &[],
             amount,
+            decimals,
         )?,
-        &[source, destination, authority, token_program],
+        &[source, mint, destination, authority, token_program],
         &[authority_signer_seeds],
     );
 
@@ -138,7 +246,7 @@ pub fn spl_token_mint(params: TokenMintParams<'_, '_>) -> ProgramResult {
     } = params;
 
     let result = invoke_signed(
-        &spl_token::instruction::mint_to(
+        &spl_token_2022::instruction::mint_to(
             token_program.key,
             mint.key,
             to.key,
@@ -165,9 +273,18 @@ pub fn spl_init_token_account(params: InitializeTokenAccountParams<'_, '_>) -> P
         rent,
     } = params;
 
+    // token-2022 accounts make room for the extensions required by the mint
+    let account_len = {
+        let mint_data = mint.try_borrow_data()?;
+        let mint_state = StateWithExtensions::<SplMint>::unpack(&mint_data)?;
+        ExtensionType::get_account_len::<SplTokenAccount>(
+            &ExtensionType::get_required_init_account_extensions(&mint_state.get_extension_types()?),
+        )
+    };
+
     create_pda_account(
         &payer,
-        spl_token::state::Account::LEN,
+        account_len,
         token_program.key,
         &system_program,
         &account,
@@ -175,7 +292,7 @@ pub fn spl_init_token_account(params: InitializeTokenAccountParams<'_, '_>) -> P
     )?;
 
     let result = invoke(
-        &spl_token::instruction::initialize_account(
+        &spl_token_2022::instruction::initialize_account(
             token_program.key,
             account.key,
             mint.key,
@@ -198,7 +315,7 @@ pub fn spl_set_authority(params: SetAuthorityParams<'_, '_>) -> ProgramResult {
     } = params;
 
     let result = invoke_signed(
-        &spl_token::instruction::set_authority(
+        &spl_token_2022::instruction::set_authority(
             token_program.key,
             account.key,
             Some(new_authority.key),
@@ -223,7 +340,7 @@ pub fn spl_close_account(params: CloseAccountParams<'_, '_>) -> ProgramResult {
     } = params;
 
     let result = invoke_signed(
-        &spl_token::instruction::close_account(
+        &spl_token_2022::instruction::close_account(
             token_program.key,
             account.key,
             destination.key,
@@ -237,21 +354,185 @@ pub fn spl_close_account(params: CloseAccountParams<'_, '_>) -> ProgramResult {
     result.map_err(|_| ErrorCode::CloseAccountFailed.into())
 }
 
+pub fn spl_token_burn(params: TokenBurnParams<'_>) -> ProgramResult {
+    let TokenBurnParams {
+        mint,
+        source,
+        amount,
+        authority,
+        token_program,
+    } = params;
+
+    let result = invoke(
+        &spl_token_2022::instruction::burn(
+            token_program.key,
+            source.key,
+            mint.key,
+            authority.key,
+            &[],
+            amount,
+        )?,
+        &[source, mint, authority, token_program],
+    );
+
+    result.map_err(|_| ErrorCode::BurnFailed.into())
+}
+
+pub fn spl_approve(params: ApproveParams<'_>) -> ProgramResult {
+    let ApproveParams {
+        source,
+        delegate,
+        owner,
+        amount,
+        token_program,
+    } = params;
+
+    let result = invoke(
+        &spl_token_2022::instruction::approve(
+            token_program.key,
+            source.key,
+            delegate.key,
+            owner.key,
+            &[],
+            amount,
+        )?,
+        &[source, delegate, owner, token_program],
+    );
+
+    result.map_err(|_| ErrorCode::ApproveFailed.into())
+}
+
+pub fn spl_revoke(params: RevokeParams<'_>) -> ProgramResult {
+    let RevokeParams {
+        source,
+        owner,
+        token_program,
+    } = params;
+
+    let result = invoke(
+        &spl_token_2022::instruction::revoke(token_program.key, source.key, owner.key, &[])?,
+        &[source, owner, token_program],
+    );
+
+    result.map_err(|_| ErrorCode::RevokeFailed.into())
+}
+
+pub fn metadata_freeze_delegated_account(params: DelegatedAccountParams<'_, '_>) -> ProgramResult {
+    let DelegatedAccountParams {
+        delegate,
+        delegate_signer_seeds,
+        token_account,
+        edition,
+        mint,
+        token_program,
+        token_metadata_program,
+    } = params;
+
+    let result = invoke_signed(
+        &mpl_token_metadata::instruction::freeze_delegated_account(
+            *token_metadata_program.key,
+            *delegate.key,
+            *token_account.key,
+            *edition.key,
+            *mint.key,
+        ),
+        &[delegate, token_account, edition, mint, token_program, token_metadata_program],
+        &[delegate_signer_seeds],
+    );
+
+    result.map_err(|_| ErrorCode::FreezeAccountFailed.into())
+}
+
+pub fn metadata_thaw_delegated_account(params: DelegatedAccountParams<'_, '_>) -> ProgramResult {
+    let DelegatedAccountParams {
+        delegate,
+        delegate_signer_seeds,
+        token_account,
+        edition,
+        mint,
+        token_program,
+        token_metadata_program,
+    } = params;
+
+    let result = invoke_signed(
+        &mpl_token_metadata::instruction::thaw_delegated_account(
+            *token_metadata_program.key,
+            *delegate.key,
+            *token_account.key,
+            *edition.key,
+            *mint.key,
+        ),
+        &[delegate, token_account, edition, mint, token_program, token_metadata_program],
+        &[delegate_signer_seeds],
+    );
+
+    result.map_err(|_| ErrorCode::ThawAccountFailed.into())
+}
+
+pub fn is_token_program(program_id: &Pubkey) -> bool {
+    *program_id == spl_token::id() || *program_id == spl_token_2022::id()
+}
+
+pub fn assert_token_program(mint: &AccountInfo, token_program: &AccountInfo) -> ProgramResult {
+    if !is_token_program(token_program.key) || mint.owner != token_program.key {
+        return Err(ErrorCode::InvalidTokenProgram.into());
+    }
+
+    Ok(())
+}
+
+pub fn unpack_token_account(token_account: &AccountInfo) -> Result<SplTokenAccount> {
+    if !is_token_program(token_account.owner) {
+        return Err(ErrorCode::InvalidTokenProgram.into());
+    }
+
+    let token_account_data = token_account.try_borrow_data()?;
+    let token_account_state = StateWithExtensions::<SplTokenAccount>::unpack(&token_account_data)?;
+
+    Ok(token_account_state.base)
+}
+
+pub fn unpack_mint(mint: &AccountInfo) -> Result<SplMint> {
+    if !is_token_program(mint.owner) {
+        return Err(ErrorCode::InvalidTokenProgram.into());
+    }
+
+    let mint_data = mint.try_borrow_data()?;
+    let mint_state = StateWithExtensions::<SplMint>::unpack(&mint_data)?;
+
+    Ok(mint_state.base)
+}
+
+pub fn assert_recipe_valid(inputs: &[RecipeInput], output_amount: u64) -> ProgramResult {
+    if inputs.is_empty()
+        || inputs.len() > MAX_RECIPE_INPUTS
+        || inputs.iter().any(|input| input.amount == 0)
+        || output_amount == 0
+    {
+        return Err(ErrorCode::InvalidRecipe.into());
+    }
+
+    Ok(())
+}
+
 pub fn assert_metadata_valid<'info>(
     nft_metadata: &AccountInfo,
+    nft_edition: &AccountInfo,
     mint: &Pubkey,
     staking_account: Box<Account<StakingAccount>>,
-) -> ProgramResult {
+    eligibility_rules_account: &EligibilityRulesAccount,
+    aurorian_stats: &AurorianStatsAccount,
+) -> Result<Metadata> {
     // determine metaplex program id
-    assert_owned_by(nft_metadata, &metaplex_token_metadata::id())?;
+    assert_owned_by(nft_metadata, &mpl_token_metadata::id())?;
 
     // determine metadata mint
     assert_derivation(
-        &metaplex_token_metadata::id(),
+        &mpl_token_metadata::id(),
         nft_metadata,
         &[
-            metaplex_token_metadata::state::PREFIX.as_bytes(),
-            metaplex_token_metadata::id().as_ref(),
+            PREFIX.as_bytes(),
+            mpl_token_metadata::id().as_ref(),
             mint.as_ref(),
         ],
     )?;
@@ -261,6 +542,36 @@ pub fn assert_metadata_valid<'info>(
 
     let metadata = Metadata::from_account_info(&nft_metadata)?;
 
+    // determine eligibility rules, they replace the authorized collection, name start and creator
+    if !eligibility_rules_account.rules.is_empty() {
+        let edition_key = edition_key_of(nft_edition, mint)?;
+
+        if !eligibility_rule_matches(
+            &eligibility_rules_account.rules,
+            0,
+            mint,
+            &metadata,
+            edition_key,
+            aurorian_stats,
+        ) {
+            return Err(ErrorCode::NotEligible.into());
+        }
+
+        return Ok(metadata);
+    }
+
+    // determine authorized verified collection
+    if let Some(collection) = &metadata.collection {
+        if collection.verified
+            && staking_account
+                .authorized_collections
+                .iter()
+                .any(|authorized_collection| *authorized_collection == collection.key)
+        {
+            return Ok(metadata);
+        }
+    }
+
     // determine authorized name start
     if staking_account.authorized_name_starts.len() > 0
         && staking_account
@@ -278,17 +589,19 @@ pub fn assert_metadata_valid<'info>(
     }
 
     // determine authorized creator
-    match metadata.data.creators {
+    match &metadata.data.creators {
         Some(creators) => {
             // determine authorized creator
             if creators.iter().find(|&creator| {
-                creator.verified && creator.address == staking_account.authorized_creator
+                creator.verified
+                    && (creator.address == staking_account.authorized_creator
+                        || staking_account.authorized_creators.contains(&creator.address))
             }) == None
             {
                 return Err(ErrorCode::NoAuthorizedCreatorsFoundInMetadata.into());
             }
 
-            return Ok(());
+            return Ok(metadata);
         }
         None => {
             return Err(ErrorCode::NoAuthorizedCreatorsFoundInMetadata.into());
@@ -296,6 +609,693 @@ pub fn assert_metadata_valid<'info>(
     };
 }
 
+// Returns the first verified creator, the default pubkey if none is verified
+pub fn primary_verified_creator_of(metadata: &Metadata) -> Pubkey {
+    metadata
+        .data
+        .creators
+        .as_ref()
+        .and_then(|creators| creators.iter().find(|creator| creator.verified))
+        .map_or(Pubkey::default(), |creator| creator.address)
+}
+
+pub fn verified_collection_of(metadata: &Metadata) -> Option<Pubkey> {
+    metadata
+        .collection
+        .as_ref()
+        .filter(|collection| collection.verified)
+        .map(|collection| collection.key)
+}
+
+// Returns the metaplex key of the edition account, none if the mint has no edition
+pub fn edition_key_of(nft_edition: &AccountInfo, mint: &Pubkey) -> Result<Option<Key>> {
+    assert_derivation(
+        &mpl_token_metadata::id(),
+        nft_edition,
+        &[
+            PREFIX.as_bytes(),
+            mpl_token_metadata::id().as_ref(),
+            mint.as_ref(),
+            EDITION.as_bytes(),
+        ],
+    )?;
+
+    if nft_edition.data_is_empty() || nft_edition.owner != &mpl_token_metadata::id() {
+        return Ok(None);
+    }
+
+    let data = nft_edition.try_borrow_data()?;
+    let edition_key = match data[0] {
+        key if key == Key::EditionV1 as u8 => Some(Key::EditionV1),
+        key if key == Key::MasterEditionV1 as u8 => Some(Key::MasterEditionV1),
+        key if key == Key::MasterEditionV2 as u8 => Some(Key::MasterEditionV2),
+        _ => None,
+    };
+
+    Ok(edition_key)
+}
+
+pub fn assert_eligibility_rules_valid(rules: &[EligibilityRule]) -> ProgramResult {
+    if rules.len() > MAX_ELIGIBILITY_RULES {
+        return Err(ErrorCode::InvalidEligibilityRules.into());
+    }
+
+    // child rules must come after their parent, which keeps the tree acyclic
+    for (index, rule) in rules.iter().enumerate() {
+        let child_indexes: &[u8] = match rule {
+            EligibilityRule::All { indexes } | EligibilityRule::Any { indexes } => indexes,
+            EligibilityRule::Not { index } => std::slice::from_ref(index),
+            _ => &[],
+        };
+
+        if child_indexes
+            .iter()
+            .any(|&child_index| child_index as usize <= index || child_index as usize >= rules.len())
+        {
+            return Err(ErrorCode::InvalidEligibilityRules.into());
+        }
+    }
+
+    Ok(())
+}
+
+pub fn eligibility_rule_matches(
+    rules: &[EligibilityRule],
+    index: usize,
+    mint: &Pubkey,
+    metadata: &Metadata,
+    edition_key: Option<Key>,
+    aurorian_stats: &AurorianStatsAccount,
+) -> bool {
+    // metaplex pads the name and symbol with null characters
+    let name = metadata.data.name.trim_end_matches(char::from(0));
+    let symbol = metadata.data.symbol.trim_end_matches(char::from(0));
+
+    match &rules[index] {
+        EligibilityRule::All { indexes } => indexes.iter().all(|&child_index| {
+            eligibility_rule_matches(
+                rules,
+                child_index as usize,
+                mint,
+                metadata,
+                edition_key,
+                aurorian_stats,
+            )
+        }),
+        EligibilityRule::Any { indexes } => indexes.iter().any(|&child_index| {
+            eligibility_rule_matches(
+                rules,
+                child_index as usize,
+                mint,
+                metadata,
+                edition_key,
+                aurorian_stats,
+            )
+        }),
+        EligibilityRule::Not { index } => !eligibility_rule_matches(
+            rules,
+            *index as usize,
+            mint,
+            metadata,
+            edition_key,
+            aurorian_stats,
+        ),
+        EligibilityRule::VerifiedCreator { creators } => match &metadata.data.creators {
+            Some(metadata_creators) => metadata_creators
+                .iter()
+                .any(|creator| creator.verified && creators.contains(&creator.address)),
+            None => false,
+        },
+        EligibilityRule::VerifiedCollection { collections } => match &metadata.collection {
+            Some(collection) => collection.verified && collections.contains(&collection.key),
+            None => false,
+        },
+        EligibilityRule::Symbol { symbol: rule_symbol } => symbol == rule_symbol,
+        EligibilityRule::UpdateAuthority { update_authority } => {
+            metadata.update_authority == *update_authority
+        }
+        EligibilityRule::Name { name: rule_name } => name == rule_name,
+        EligibilityRule::NameStartsWith { prefix } => name.starts_with(prefix.as_str()),
+        EligibilityRule::NameEndsWith { suffix } => name.ends_with(suffix.as_str()),
+        EligibilityRule::MasterEdition => {
+            edition_key == Some(Key::MasterEditionV1) || edition_key == Some(Key::MasterEditionV2)
+        }
+        EligibilityRule::Edition => edition_key == Some(Key::EditionV1),
+        EligibilityRule::MintAllowList { mints } => mints.contains(mint),
+        EligibilityRule::MintDenyList { mints } => !mints.contains(mint),
+        EligibilityRule::MinimumLevel { level } => aurorian_stats.level >= *level,
+    }
+}
+
+pub fn assert_party_rules_valid(party_rules: &PartyRules) -> ProgramResult {
+    if party_rules.min_party_size == 0
+        || party_rules.min_party_size > party_rules.max_party_size
+        || party_rules.constraints.len() > MAX_PARTY_CONSTRAINTS
+    {
+        return Err(ErrorCode::InvalidPartyRules.into());
+    }
+
+    for constraint in party_rules.constraints.iter() {
+        if constraint.min > constraint.max || !party_member_valid(&constraint.member) {
+            return Err(ErrorCode::InvalidPartyRules.into());
+        }
+    }
+
+    Ok(())
+}
+
+pub fn assert_synergies_valid(synergies: &[Synergy]) -> ProgramResult {
+    if synergies.len() > MAX_SYNERGIES {
+        return Err(ErrorCode::InvalidSynergies.into());
+    }
+
+    for synergy in synergies.iter() {
+        if synergy.count == 0 || !party_member_valid(&synergy.member) {
+            return Err(ErrorCode::InvalidSynergies.into());
+        }
+    }
+
+    Ok(())
+}
+
+pub fn assert_destination_config_valid(config: &DestinationConfig) -> ProgramResult {
+    if config.name.len() > MAX_NAME_LENGTH
+        || config.allowed_durations.len() > MAX_ALLOWED_DURATIONS
+        || config.allowed_durations.iter().any(|&duration| duration == 0)
+    {
+        return Err(ErrorCode::InvalidDestination.into());
+    }
+
+    assert_party_rules_valid(&config.party_rules)
+}
+
+pub fn party_member_valid(member: &PartyMember) -> bool {
+    match member {
+        PartyMember::NamePrefix { prefix } => prefix.len() <= MAX_NAME_LENGTH,
+        _ => true,
+    }
+}
+
+pub fn party_member_matches(member: &PartyMember, staked_nft: &StakedNft) -> bool {
+    match member {
+        PartyMember::NamePrefix { prefix } => staked_nft.name.starts_with(prefix.as_str()),
+        PartyMember::Traits { traits } => staked_nft.traits & traits == *traits,
+        PartyMember::MinimumRarity { rarity } => staked_nft.rarity >= *rarity,
+    }
+}
+
+pub fn assert_party_valid(party_rules: &PartyRules, staked_nfts: &[StakedNft]) -> ProgramResult {
+    // an expedition always needs at least one aurorian
+    let party_size = staked_nfts.len();
+    if party_size == 0
+        || party_size < party_rules.min_party_size as usize
+        || party_size > party_rules.max_party_size as usize
+    {
+        return Err(ErrorCode::InvalidPartySize.into());
+    }
+
+    for constraint in party_rules.constraints.iter() {
+        let members = staked_nfts
+            .iter()
+            .filter(|staked_nft| party_member_matches(&constraint.member, staked_nft))
+            .count();
+
+        if members < constraint.min as usize || members > constraint.max as usize {
+            return Err(ErrorCode::InvalidPartyComposition.into());
+        }
+    }
+
+    Ok(())
+}
+
+// Returns the multiplier in basis points, the bonuses of every matched synergy add up
+pub fn synergy_multiplier_for(synergies: &[Synergy], staked_nfts: &[StakedNft]) -> u64 {
+    synergies
+        .iter()
+        .filter(|synergy| {
+            staked_nfts
+                .iter()
+                .filter(|staked_nft| party_member_matches(&synergy.member, staked_nft))
+                .count()
+                >= synergy.count as usize
+        })
+        .fold(BASIS_POINTS, |multiplier, synergy| multiplier + synergy.bonus as u64)
+}
+
+// Returns the emission rate of the highest tier reached by the staking period
+pub fn aury_emission_rate_for(aury_emission_rates: &[AuryEmissionRate], staking_period: u64) -> u64 {
+    aury_emission_rates
+        .iter()
+        .filter(|aury_emission_rate| aury_emission_rate.minimum_staking_period <= staking_period)
+        .max_by_key(|aury_emission_rate| aury_emission_rate.minimum_staking_period)
+        .map_or(0, |aury_emission_rate| aury_emission_rate.rate)
+}
+
+// Returns the aury emission rate of a party for the staking period, weighted by rarity and raised by
+// its equipment and synergies
+pub fn aury_emission_rate_of(
+    config: &PositionConfig,
+    staked_nfts: &[StakedNft],
+    aury_boost: u64,
+    synergy_multiplier: u64,
+    staking_period: u64,
+) -> Result<u64> {
+    let aury_emission_weight: u64 = staked_nfts
+        .iter()
+        .map(|staked_nft| rarity_multiplier_for(&config.rarity_multipliers, staked_nft.rarity))
+        .sum();
+    let aury_emission_rate = aury_emission_rate_for(&config.aury_emission_rates, staking_period)
+        .checked_mul(aury_emission_weight)
+        .ok_or(ErrorCode::NumericalOverflow)?
+        / BASIS_POINTS;
+    let aury_emission_rate = aury_emission_rate
+        .checked_mul(BASIS_POINTS + aury_boost)
+        .ok_or(ErrorCode::NumericalOverflow)?
+        / BASIS_POINTS;
+    let aury_emission_rate = aury_emission_rate
+        .checked_mul(synergy_multiplier)
+        .ok_or(ErrorCode::NumericalOverflow)?
+        / BASIS_POINTS;
+
+    Ok(aury_emission_rate)
+}
+
+// Returns the rarity multiplier in basis points, unlisted rarities aren't weighted
+pub fn rarity_multiplier_for(rarity_multipliers: &[u16], rarity: u8) -> u64 {
+    rarity_multipliers
+        .get(rarity as usize)
+        .map_or(BASIS_POINTS, |&rarity_multiplier| rarity_multiplier as u64)
+}
+
+// Returns the xp earned by an expedition, weighted by the rarity multiplier
+pub fn aurorian_xp_for(rarity_multipliers: &[u16], rarity: u8, staking_period: u64) -> Result<u64> {
+    Ok((staking_period / XP_PERIOD)
+        .checked_mul(rarity_multiplier_for(rarity_multipliers, rarity))
+        .ok_or(ErrorCode::NumericalOverflow)?
+        / BASIS_POINTS)
+}
+
+pub fn aurorian_level_for(xp: u64) -> u16 {
+    (xp / XP_PER_LEVEL).min(u16::MAX as u64) as u16
+}
+
+// Returns the lowest rarity and no traits for an aurorian the admin hasn't registered
+pub fn load_aurorian_registry(
+    program_id: &Pubkey,
+    aurorian_registry_info: &AccountInfo,
+    nft_mint: &Pubkey,
+) -> Result<AurorianRegistryAccount> {
+    assert_derivation(
+        program_id,
+        aurorian_registry_info,
+        &[constants::AURORIAN_REGISTRY_PDA_SEED, nft_mint.as_ref()],
+    )?;
+
+    if aurorian_registry_info.data_is_empty() {
+        return Ok(AurorianRegistryAccount {
+            nft_mint: *nft_mint,
+            ..Default::default()
+        });
+    }
+
+    assert_owned_by(aurorian_registry_info, program_id)?;
+    let data = aurorian_registry_info.try_borrow_data()?;
+    let aurorian_registry = AurorianRegistryAccount::try_deserialize(&mut &data[..])?;
+    if aurorian_registry.nft_mint != *nft_mint {
+        return Err(ErrorCode::InvalidAurorianRegistry.into());
+    }
+
+    Ok(aurorian_registry)
+}
+
+// Returns empty stats for an aurorian that never completed an expedition
+pub fn load_aurorian_stats(
+    program_id: &Pubkey,
+    aurorian_stats_info: &AccountInfo,
+    nft_mint: &Pubkey,
+) -> Result<AurorianStatsAccount> {
+    assert_derivation(
+        program_id,
+        aurorian_stats_info,
+        &[constants::AURORIAN_STATS_PDA_SEED, nft_mint.as_ref()],
+    )?;
+
+    if aurorian_stats_info.data_is_empty() {
+        return Ok(AurorianStatsAccount {
+            nft_mint: *nft_mint,
+            ..Default::default()
+        });
+    }
+
+    assert_owned_by(aurorian_stats_info, program_id)?;
+    let data = aurorian_stats_info.try_borrow_data()?;
+    let aurorian_stats = AurorianStatsAccount::try_deserialize(&mut &data[..])?;
+    if aurorian_stats.nft_mint != *nft_mint {
+        return Err(ErrorCode::InvalidAurorianStats.into());
+    }
+
+    Ok(aurorian_stats)
+}
+
+// Creates the stats pda on the first expedition
+pub fn save_aurorian_stats(params: AurorianStatsParams<'_, '_>) -> ProgramResult {
+    let AurorianStatsParams {
+        aurorian_stats,
+        aurorian_stats_info,
+        payer,
+        system_program,
+        program_id,
+    } = params;
+
+    if aurorian_stats_info.data_is_empty() {
+        let bump = assert_derivation(
+            program_id,
+            &aurorian_stats_info,
+            &[
+                constants::AURORIAN_STATS_PDA_SEED,
+                aurorian_stats.nft_mint.as_ref(),
+            ],
+        )?;
+
+        create_pda_account(
+            &payer,
+            AURORIAN_STATS_SPACE,
+            program_id,
+            &system_program,
+            &aurorian_stats_info,
+            &[
+                constants::AURORIAN_STATS_PDA_SEED,
+                aurorian_stats.nft_mint.as_ref(),
+                &[bump],
+            ],
+        )?;
+    }
+
+    let mut data = aurorian_stats_info.try_borrow_mut_data()?;
+    let mut cursor = std::io::Cursor::new(&mut data[..]);
+    aurorian_stats.try_serialize(&mut cursor)?;
+
+    Ok(())
+}
+
+pub fn load_wallet_positions(
+    program_id: &Pubkey,
+    wallet_positions_info: &AccountInfo,
+    wallet: &Pubkey,
+) -> Result<WalletPositionsAccount> {
+    assert_derivation(
+        program_id,
+        wallet_positions_info,
+        &[constants::WALLET_POSITIONS_PDA_SEED, wallet.as_ref()],
+    )?;
+
+    if wallet_positions_info.data_is_empty() {
+        return Ok(WalletPositionsAccount {
+            wallet: *wallet,
+            ..Default::default()
+        });
+    }
+
+    assert_owned_by(wallet_positions_info, program_id)?;
+    let data = wallet_positions_info.try_borrow_data()?;
+    let wallet_positions = WalletPositionsAccount::try_deserialize(&mut &data[..])?;
+
+    Ok(wallet_positions)
+}
+
+// Creates the wallet positions pda on the first lock_stake
+pub fn save_wallet_positions(params: WalletPositionsParams<'_, '_>) -> ProgramResult {
+    let WalletPositionsParams {
+        wallet_positions,
+        wallet_positions_info,
+        payer,
+        system_program,
+        program_id,
+    } = params;
+
+    if wallet_positions_info.data_is_empty() {
+        let bump = assert_derivation(
+            program_id,
+            &wallet_positions_info,
+            &[
+                constants::WALLET_POSITIONS_PDA_SEED,
+                wallet_positions.wallet.as_ref(),
+            ],
+        )?;
+
+        create_pda_account(
+            &payer,
+            WALLET_POSITIONS_SPACE,
+            program_id,
+            &system_program,
+            &wallet_positions_info,
+            &[
+                constants::WALLET_POSITIONS_PDA_SEED,
+                wallet_positions.wallet.as_ref(),
+                &[bump],
+            ],
+        )?;
+    }
+
+    let mut data = wallet_positions_info.try_borrow_mut_data()?;
+    let mut cursor = std::io::Cursor::new(&mut data[..]);
+    wallet_positions.try_serialize(&mut cursor)?;
+
+    Ok(())
+}
+
+pub fn init_stake_record(params: StakeRecordParams<'_, '_>) -> ProgramResult {
+    let StakeRecordParams {
+        stake_record,
+        stake_record_info,
+        payer,
+        system_program,
+        program_id,
+    } = params;
+
+    let bump = assert_derivation(
+        program_id,
+        &stake_record_info,
+        &[constants::STAKE_RECORD_PDA_SEED, stake_record.nft_mint.as_ref()],
+    )?;
+
+    if !stake_record_info.data_is_empty() {
+        return Err(ErrorCode::NftAlreadyStaked.into());
+    }
+
+    create_pda_account(
+        &payer,
+        STAKE_RECORD_SPACE,
+        program_id,
+        &system_program,
+        &stake_record_info,
+        &[
+            constants::STAKE_RECORD_PDA_SEED,
+            stake_record.nft_mint.as_ref(),
+            &[bump],
+        ],
+    )?;
+
+    let mut data = stake_record_info.try_borrow_mut_data()?;
+    let mut cursor = std::io::Cursor::new(&mut data[..]);
+    stake_record.try_serialize(&mut cursor)?;
+
+    Ok(())
+}
+
+// Closes the stake record of an nft unstaked from the given position
+pub fn close_stake_record<'info>(
+    program_id: &Pubkey,
+    stake_record_info: &AccountInfo<'info>,
+    nft_mint: &Pubkey,
+    user_staking_account: &UserStakingAccount,
+    sol_destination: AccountInfo<'info>,
+) -> ProgramResult {
+    assert_derivation(
+        program_id,
+        stake_record_info,
+        &[constants::STAKE_RECORD_PDA_SEED, nft_mint.as_ref()],
+    )?;
+    assert_owned_by(stake_record_info, program_id)?;
+
+    let stake_record = {
+        let data = stake_record_info.try_borrow_data()?;
+        StakeRecordAccount::try_deserialize(&mut &data[..])?
+    };
+    if stake_record.wallet != user_staking_account.wallet
+        || stake_record.user_staking_index != user_staking_account.index
+    {
+        return Err(ErrorCode::InvalidStakeRecord.into());
+    }
+
+    close(stake_record_info.clone(), sol_destination)
+}
+
+pub fn assert_not_blocked(nft_mint: &Pubkey, mint_blocklist_account: &MintBlocklistAccount) -> ProgramResult {
+    if mint_blocklist_account
+        .blocked_mints
+        .iter()
+        .any(|blocked_mint| blocked_mint.nft_mint == *nft_mint)
+    {
+        return Err(ErrorCode::MintBlocked.into());
+    }
+
+    Ok(())
+}
+
+pub fn assert_staked_nfts_not_blocked(
+    nft_mint_keys: &[Pubkey],
+    mint_blocklist_account: &MintBlocklistAccount,
+) -> ProgramResult {
+    for nft_mint_key in nft_mint_keys.iter() {
+        assert_not_blocked(nft_mint_key, mint_blocklist_account)?;
+    }
+
+    Ok(())
+}
+
+pub fn staked_nfts_of(user_staking_account: &UserStakingAccount) -> Vec<StakedNft> {
+    // nfts staked before the registry count as unregistered aurorians
+    user_staking_account
+        .nft_mint_keys
+        .iter()
+        .map(|nft_mint_key| {
+            user_staking_account
+                .staked_nfts
+                .iter()
+                .find(|staked_nft| staked_nft.nft_mint == *nft_mint_key)
+                .cloned()
+                .unwrap_or(StakedNft {
+                    nft_mint: *nft_mint_key,
+                    ..Default::default()
+                })
+        })
+        .collect()
+}
+
+pub fn position_config_of(
+    user_staking_account: &UserStakingAccount,
+    staking_account: &StakingAccount,
+) -> PositionConfig {
+    if user_staking_account.config.version > 0 {
+        return user_staking_account.config.clone();
+    }
+
+    // positions locked before the terms were captured settle under the live ones
+    PositionConfig {
+        version: 0,
+        minimum_staking_period: staking_account.minimum_staking_period,
+        maximum_staking_period: staking_account.maximum_staking_period,
+        rarity_multipliers: staking_account.rarity_multipliers.clone(),
+        aury_emission_rates: staking_account.aury_emission_rates.clone(),
+        expedition_cooldown: staking_account.expedition_cooldown,
+        ..Default::default()
+    }
+}
+
+pub fn remove_staked_nft(user_staking_account: &mut UserStakingAccount, nft_mint: &Pubkey) {
+    user_staking_account
+        .nft_mint_keys
+        .retain(|nft_mint_key| nft_mint_key != nft_mint);
+    user_staking_account
+        .staked_nfts
+        .retain(|staked_nft| staked_nft.nft_mint != *nft_mint);
+}
+
+pub fn accrue_aury_emission(user_staking_account: &mut UserStakingAccount) -> ProgramResult {
+    // emission stops at the end of the staking period
+    let accrue_until = (Clock::get()?.unix_timestamp as u64)
+        .min(user_staking_account.staking_at + user_staking_account.staking_period);
+
+    if user_staking_account.aury_emission_rate == 0
+        || accrue_until <= user_staking_account.last_aury_accrual_at
+    {
+        return Ok(());
+    }
+
+    let aury_amount = (accrue_until - user_staking_account.last_aury_accrual_at)
+        .checked_mul(user_staking_account.aury_emission_rate)
+        .ok_or(ErrorCode::NumericalOverflow)?;
+
+    user_staking_account.claimable_aury_amount = user_staking_account
+        .claimable_aury_amount
+        .checked_add(aury_amount)
+        .ok_or(ErrorCode::NumericalOverflow)?;
+    user_staking_account.last_aury_accrual_at = accrue_until;
+
+    Ok(())
+}
+
+// A position holding a blocked nft stops earning, its emission reserve returns to the pool
+pub fn forfeit_blocked_aury_emission(
+    staking_account: &mut StakingAccount,
+    user_staking_account: &mut UserStakingAccount,
+    mint_blocklist_account: &MintBlocklistAccount,
+) -> ProgramResult {
+    if user_staking_account.aury_emission_rate == 0
+        || !user_staking_account.nft_mint_keys.iter().any(|nft_mint_key| {
+            mint_blocklist_account
+                .blocked_mints
+                .iter()
+                .any(|blocked_mint| blocked_mint.nft_mint == *nft_mint_key)
+        })
+    {
+        return Ok(());
+    }
+
+    let return_at = user_staking_account.staking_at + user_staking_account.staking_period;
+    let aury_emission_refund = user_staking_account
+        .aury_emission_rate
+        .checked_mul(return_at.saturating_sub(user_staking_account.last_aury_accrual_at))
+        .ok_or(ErrorCode::NumericalOverflow)?;
+    staking_account.aury_emission_pool = staking_account
+        .aury_emission_pool
+        .checked_add(aury_emission_refund)
+        .ok_or(ErrorCode::NumericalOverflow)?;
+    user_staking_account.aury_emission_rate = 0;
+
+    Ok(())
+}
+
+pub fn release_cancelled_position(
+    staking_account: &mut StakingAccount,
+    user_staking_account: &mut UserStakingAccount,
+) -> ProgramResult {
+    // keep the aury emitted so far and return the rest of the reserve to the pool
+    accrue_aury_emission(user_staking_account)?;
+
+    let now = Clock::get()?.unix_timestamp as u64;
+    let return_at = user_staking_account.staking_at + user_staking_account.staking_period;
+    let aury_emission_refund = user_staking_account
+        .aury_emission_rate
+        .checked_mul(return_at.saturating_sub(now.max(user_staking_account.last_aury_accrual_at)))
+        .ok_or(ErrorCode::NumericalOverflow)?;
+    staking_account.aury_emission_pool = staking_account
+        .aury_emission_pool
+        .checked_add(aury_emission_refund)
+        .ok_or(ErrorCode::NumericalOverflow)?;
+    user_staking_account.aury_emission_rate = 0;
+
+    // the entry fee funded the emission pool, it's taken back once
+    let entry_fee = user_staking_account.config.entry_fee;
+    staking_account.aury_emission_pool = staking_account
+        .aury_emission_pool
+        .checked_sub(entry_fee)
+        .ok_or(ErrorCode::InsufficientAuryEmissionPool)?;
+    user_staking_account.config.entry_fee = 0;
+
+    // the deposit and the entry fee are paid back with the next aury claim
+    user_staking_account.claimable_aury_amount = user_staking_account
+        .claimable_aury_amount
+        .checked_add(user_staking_account.aury_deposit)
+        .ok_or(ErrorCode::NumericalOverflow)?
+        .checked_add(entry_fee)
+        .ok_or(ErrorCode::NumericalOverflow)?;
+    user_staking_account.aury_deposit = 0;
+
+    Ok(())
+}
+
 pub fn assert_derivation(program_id: &Pubkey, account: &AccountInfo, path: &[&[u8]]) -> Result<u8> {
     let (key, bump) = Pubkey::find_program_address(&path, program_id);
     if key != *account.key {
@@ -369,6 +1369,31 @@ pub fn create_pda_account<'a>(
     }
 }
 
+pub fn realloc_account<'a>(
+    payer: &AccountInfo<'a>,
+    space: usize,
+    system_program: &AccountInfo<'a>,
+    account: &AccountInfo<'a>,
+) -> ProgramResult {
+    if account.data_len() >= space {
+        return Ok(());
+    }
+
+    // top up the rent of the grown account
+    let required_lamports = Rent::get()?
+        .minimum_balance(space)
+        .saturating_sub(account.lamports());
+    if required_lamports > 0 {
+        invoke(
+            &system_instruction::transfer(payer.key, account.key, required_lamports),
+            &[payer.clone(), account.clone(), system_program.clone()],
+        )?;
+    }
+
+    // the new fields read as zero
+    account.realloc(space, true)
+}
+
 pub fn close<'info>(info: AccountInfo<'info>, sol_destination: AccountInfo<'info>) -> ProgramResult {
     // Transfer tokens from the account to the sol_destination.
     let dest_starting_lamports = sol_destination.lamports();
@@ -15,6 +15,7 @@ import {
   createTokenMint,
   sleep,
   createAssociatedTokenAccount,
+  createMasterEdition,
 } from "./utils";
 import fs from "fs";
 import dayjs from "dayjs";
@@ -29,6 +30,13 @@ const filepath = "target/idl/nft_staking.json";
 const idlStr = fs.readFileSync(filepath);
 const idl = JSON.parse(idlStr.toString());
 
+const METADATA_PROGRAM_ID = new PublicKey(
+  "metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s"
+);
+const TOKEN_2022_PROGRAM_ID = new PublicKey(
+  "TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb"
+);
+
 const envProvider = anchor.Provider.env();
 
 let provider = envProvider;
@@ -63,8 +71,51 @@ describe("nft-staking", () => {
   let nftVaultPubkey: PublicKey[] = [];
   let nftVaultBump: number[] = [];
 
+  let nftRegistryPubkey: PublicKey[] = [];
+  let nftRegistryBump: number[] = [];
+  let nftStatsPubkey: PublicKey[] = [];
+  let nftStakeRecordPubkey: PublicKey[] = [];
+
+  let nftEditionPubkey: PublicKey[] = [];
+
+  let eligibilityRulesPubkey: PublicKey;
+  let eligibilityRulesBump: number;
+  let mintBlocklistPubkey: PublicKey;
+  let mintBlocklistBump: number;
+  let partyRulesPubkey: PublicKey;
+  let partyRulesBump: number;
+  let synergiesPubkey: PublicKey;
+  let synergiesBump: number;
+  let destinationId = 0;
+  let destinationPubkey: PublicKey;
+  let destinationBump: number;
+  let entryFee = 10;
+  let roundId = 1;
+  let roundPubkey: PublicKey;
+  let roundBump: number;
+  // one Helios leads every party
+  let partyRules = {
+    minPartySize: 1,
+    maxPartySize: 10,
+    constraints: [
+      {
+        member: { namePrefix: { prefix: "Helios" } },
+        min: 1,
+        max: 1,
+      },
+    ],
+  };
+  let itemVaultPubkey: PublicKey;
+  let itemVaultBump: number;
+  let rewardConfigPubkey: PublicKey;
+  let rewardConfigBump: number;
+  let recipeId = 0;
+  let recipePubkey: PublicKey;
+  let recipeBump: number;
+
   let minimumStakingPeriod = new anchor.BN(1);
-  let maximumStakingPeriod = new anchor.BN(20);
+  // an expedition extended once reaches the maximum
+  let maximumStakingPeriod = new anchor.BN(4);
   let auryDepositAmount = new anchor.BN(1e9);
 
   //winner
@@ -73,10 +124,13 @@ describe("nft-staking", () => {
   let userStakingCounterPubkey: PublicKey;
   let userStakingCounterBump: number;
 
+  let walletPositionsPubkey: PublicKey;
+
   let userStakingPubkey: PublicKey;
   let userStakingBump: number;
   let userStakingIndex = 0;
   let userStakingPeriod = new anchor.BN(2);
+  let expeditionCooldown = new anchor.BN(3600);
 
   let nextUserStakingPubkey: PublicKey;
   let nextUserStakingBump: number;
@@ -100,6 +154,13 @@ describe("nft-staking", () => {
   let nftMetadataPubkey: PublicKey[] = [];
   let nftToken: Token[] = [];
   let nftCount = 7;
+  // the Token-2022 Aurorian follows the others, its metadata is preloaded by Anchor.toml
+  let token2022Nft = nftCount;
+  let token2022NftCollection = new PublicKey(
+    "2jca9wG9YphmY8Kgkk5E3adXmJofh4d5GNdTSsxB51Zm"
+  );
+  // the master edition NFT staked in place follows it
+  let nonCustodialNft = nftCount + 1;
 
   //reward mint and metadata
   let rewardMintPubkey: PublicKey[] = [];
@@ -185,6 +246,53 @@ describe("nft-staking", () => {
 
       nftVaultPubkey.push(pubkey);
       nftVaultBump.push(bump);
+
+      // Aurorian registry pda
+      [pubkey, bump] = await anchor.web3.PublicKey.findProgramAddress(
+        [
+          Buffer.from(anchor.utils.bytes.utf8.encode("aurorian_registry")),
+          mint.publicKey.toBuffer(),
+        ],
+        program.programId
+      );
+
+      nftRegistryPubkey.push(pubkey);
+      nftRegistryBump.push(bump);
+
+      // Aurorian stats pda, created by the program on the first unstake
+      [pubkey, bump] = await anchor.web3.PublicKey.findProgramAddress(
+        [
+          Buffer.from(anchor.utils.bytes.utf8.encode("aurorian_stats")),
+          mint.publicKey.toBuffer(),
+        ],
+        program.programId
+      );
+
+      nftStatsPubkey.push(pubkey);
+
+      // Stake record pda, exists while the NFT is staked
+      [pubkey, bump] = await anchor.web3.PublicKey.findProgramAddress(
+        [
+          Buffer.from(anchor.utils.bytes.utf8.encode("stake_record")),
+          mint.publicKey.toBuffer(),
+        ],
+        program.programId
+      );
+
+      nftStakeRecordPubkey.push(pubkey);
+
+      // Edition pda, never initialized for the test NFTs
+      [pubkey, bump] = await anchor.web3.PublicKey.findProgramAddress(
+        [
+          Buffer.from(anchor.utils.bytes.utf8.encode("metadata")),
+          METADATA_PROGRAM_ID.toBuffer(),
+          mint.publicKey.toBuffer(),
+          Buffer.from(anchor.utils.bytes.utf8.encode("edition")),
+        ],
+        METADATA_PROGRAM_ID
+      );
+
+      nftEditionPubkey.push(pubkey);
     }
   });
 
@@ -199,6 +307,47 @@ describe("nft-staking", () => {
         [auryMintPubkey.toBuffer()],
         program.programId
       );
+    [eligibilityRulesPubkey, eligibilityRulesBump] =
+      await anchor.web3.PublicKey.findProgramAddress(
+        [Buffer.from(anchor.utils.bytes.utf8.encode("eligibility_rules"))],
+        program.programId
+      );
+    [mintBlocklistPubkey, mintBlocklistBump] =
+      await anchor.web3.PublicKey.findProgramAddress(
+        [Buffer.from(anchor.utils.bytes.utf8.encode("mint_blocklist"))],
+        program.programId
+      );
+    [partyRulesPubkey, partyRulesBump] =
+      await anchor.web3.PublicKey.findProgramAddress(
+        [Buffer.from(anchor.utils.bytes.utf8.encode("party_rules"))],
+        program.programId
+      );
+    [synergiesPubkey, synergiesBump] =
+      await anchor.web3.PublicKey.findProgramAddress(
+        [Buffer.from(anchor.utils.bytes.utf8.encode("synergies"))],
+        program.programId
+      );
+    [destinationPubkey, destinationBump] =
+      await anchor.web3.PublicKey.findProgramAddress(
+        [
+          Buffer.from(anchor.utils.bytes.utf8.encode("destination")),
+          Buffer.from(
+            anchor.utils.bytes.utf8.encode(
+              new anchor.BN(destinationId).toString()
+            )
+          ),
+        ],
+        program.programId
+      );
+    [roundPubkey, roundBump] = await anchor.web3.PublicKey.findProgramAddress(
+      [
+        Buffer.from(anchor.utils.bytes.utf8.encode("round")),
+        Buffer.from(
+          anchor.utils.bytes.utf8.encode(new anchor.BN(roundId).toString())
+        ),
+      ],
+      program.programId
+    );
   });
 
   it("Prepare random token", async () => {
@@ -351,6 +500,97 @@ describe("nft-staking", () => {
     );
   });
 
+  it("Migrate staking failed with non-admin", async () => {
+    const notAdmin = Keypair.generate();
+
+    await assert.rejects(
+      async () => {
+        await program.rpc.migrateStaking(stakingBump, {
+          accounts: {
+            admin: notAdmin.publicKey,
+            stakingAccount: stakingPubkey,
+            systemProgram: anchor.web3.SystemProgram.programId,
+          },
+          signers: [notAdmin],
+        });
+      },
+      {
+        code: 6000,
+        // message: '6000: Not admin',
+      }
+    );
+  });
+
+  it("Migrate staking", async () => {
+    const before = await provider.connection.getAccountInfo(stakingPubkey);
+
+    // an account already in the current layout is left untouched
+    await program.rpc.migrateStaking(stakingBump, {
+      accounts: {
+        admin: provider.wallet.publicKey,
+        stakingAccount: stakingPubkey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      },
+    });
+
+    const after = await provider.connection.getAccountInfo(stakingPubkey);
+    assert.equal(after.data.length, before.data.length);
+    assert.equal(after.lamports, before.lamports);
+  });
+
+  it("Set aurorian registry", async () => {
+    // the last Aurorian is left unregistered
+    for (let i = 0; i < nftCount - 1; i++) {
+      await program.rpc.setAurorianRegistry(
+        stakingBump,
+        nftRegistryBump[i],
+        i % 3,
+        1 << i,
+        {
+          accounts: {
+            stakingAccount: stakingPubkey,
+            nftMint: nftMintPubkey[i],
+            aurorianRegistryAccount: nftRegistryPubkey[i],
+            admin: provider.wallet.publicKey,
+            systemProgram: anchor.web3.SystemProgram.programId,
+            rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+          },
+        }
+      );
+
+      const aurorianRegistryAccount =
+        await program.account.aurorianRegistryAccount.fetch(
+          nftRegistryPubkey[i]
+        );
+      assert.equal(
+        aurorianRegistryAccount.nftMint.toString(),
+        nftMintPubkey[i].toString()
+      );
+      assert.equal(aurorianRegistryAccount.rarity, i % 3);
+      assert.equal(aurorianRegistryAccount.traits, 1 << i);
+    }
+  });
+
+  it("Update rarity config", async () => {
+    let rarityMultipliers = [10000, 15000, 20000];
+
+    await program.rpc.updateRarityConfig(stakingBump, 0, rarityMultipliers, {
+      accounts: {
+        stakingAccount: stakingPubkey,
+        admin: provider.wallet.publicKey,
+      },
+    });
+
+    const stakingAccount = await program.account.stakingAccount.fetch(
+      stakingPubkey
+    );
+    assert.equal(stakingAccount.minimumRarity, 0);
+    assert.equal(
+      stakingAccount.rarityMultipliers.toString(),
+      rarityMultipliers.toString()
+    );
+  });
+
   it("Transfer to", async () => {
     // Old balance
     let oldBalance = await getTokenBalance(userRandomTokenAccount);
@@ -359,6 +599,7 @@ describe("nft-staking", () => {
       accounts: {
         sourceTokenAccount: randomVaultPubkey,
         destTokenAccount: userRandomTokenAccount,
+        mint: randomMintPubkey,
         stakingAccount: stakingPubkey,
         admin: provider.wallet.publicKey,
         tokenProgram: TOKEN_PROGRAM_ID,
@@ -396,6 +637,41 @@ describe("nft-staking", () => {
     );
   });
 
+  it("Set reward config", async () => {
+    [rewardConfigPubkey, rewardConfigBump] =
+      await anchor.web3.PublicKey.findProgramAddress(
+        [
+          Buffer.from(anchor.utils.bytes.utf8.encode("reward_config")),
+          rewardMintPubkey[0].toBuffer(),
+        ],
+        program.programId
+      );
+
+    // Slot 1 with a 10% aury boost and a 5% odds boost, not consumable
+    await program.rpc.setRewardConfig(
+      stakingBump,
+      rewardConfigBump,
+      { slot: 1, auryBoost: 1000, oddsBoost: 500 },
+      { lockReduction: new anchor.BN(0), rewardMultiplier: 0 },
+      {
+        accounts: {
+          stakingAccount: stakingPubkey,
+          nftMint: rewardMintPubkey[0],
+          rewardConfigAccount: rewardConfigPubkey,
+          admin: provider.wallet.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+          rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        },
+      }
+    );
+
+    const rewardConfigAccount =
+      await program.account.rewardConfigAccount.fetch(rewardConfigPubkey);
+    assert.equal(rewardConfigAccount.equipment.slot, 1);
+    assert.equal(rewardConfigAccount.equipment.auryBoost, 1000);
+    assert.equal(rewardConfigAccount.equipment.oddsBoost, 500);
+  });
+
   it("Remove reward", async () => {
     // Remaining accounts - mint(writable)
     let remainingAccounts = [
@@ -478,6 +754,120 @@ describe("nft-staking", () => {
     );
   });
 
+  it("Add/Remove authorized collections", async () => {
+    let newAuthorizedCollections = [
+      Keypair.generate().publicKey,
+      Keypair.generate().publicKey,
+    ];
+
+    // Add
+    await program.rpc.addAuthorizedCollections(
+      stakingBump,
+      newAuthorizedCollections,
+      {
+        accounts: {
+          stakingAccount: stakingPubkey,
+          admin: provider.wallet.publicKey,
+        },
+      }
+    );
+
+    const stakingAccount0 = await program.account.stakingAccount.fetch(
+      stakingPubkey
+    );
+    assert.equal(
+      stakingAccount0.authorizedCollections.toString(),
+      newAuthorizedCollections.toString()
+    );
+
+    // Remove
+    await program.rpc.removeAuthorizedCollections(
+      stakingBump,
+      newAuthorizedCollections,
+      {
+        accounts: {
+          stakingAccount: stakingPubkey,
+          admin: provider.wallet.publicKey,
+        },
+      }
+    );
+
+    const stakingAccount1 = await program.account.stakingAccount.fetch(
+      stakingPubkey
+    );
+    assert.equal(stakingAccount1.authorizedCollections.toString(), [].toString());
+  });
+
+  it("Set eligibility rules", async () => {
+    // Any verified creator or a "Helios" name start
+    let rules = [
+      { any: { indexes: Buffer.from([1, 2]) } },
+      { verifiedCreator: { creators: [authorizedCreator] } },
+      { nameStartsWith: { prefix: "Helios" } },
+    ];
+
+    await program.rpc.setEligibilityRules(
+      stakingBump,
+      eligibilityRulesBump,
+      rules,
+      {
+        accounts: {
+          stakingAccount: stakingPubkey,
+          eligibilityRulesAccount: eligibilityRulesPubkey,
+          admin: provider.wallet.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+          rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        },
+      }
+    );
+
+    const eligibilityRulesAccount =
+      await program.account.eligibilityRulesAccount.fetch(
+        eligibilityRulesPubkey
+      );
+    assert.equal(eligibilityRulesAccount.rules.length, 3);
+
+    // A child rule before its parent is rejected
+    await assert.rejects(
+      async () => {
+        await program.rpc.setEligibilityRules(
+          stakingBump,
+          eligibilityRulesBump,
+          [{ not: { index: 0 } }],
+          {
+            accounts: {
+              stakingAccount: stakingPubkey,
+              eligibilityRulesAccount: eligibilityRulesPubkey,
+              admin: provider.wallet.publicKey,
+              systemProgram: anchor.web3.SystemProgram.programId,
+              rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+            },
+          }
+        );
+      },
+      {
+        code: 6031,
+        // message: '6031: Invalid eligibility rules',
+      }
+    );
+
+    // the staking flow below uses the authorized creator and name starts
+    await program.rpc.setEligibilityRules(
+      stakingBump,
+      eligibilityRulesBump,
+      [],
+      {
+        accounts: {
+          stakingAccount: stakingPubkey,
+          eligibilityRulesAccount: eligibilityRulesPubkey,
+          admin: provider.wallet.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+          rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        },
+      }
+    );
+  });
+
   it("Update staking period", async () => {
     let newMinimumStakingPeriod = new anchor.BN(5);
     let newMaximumStakingPeriod = new anchor.BN(10);
@@ -519,6 +909,61 @@ describe("nft-staking", () => {
     );
   });
 
+  it("Update aury emission rates & fund aury emission", async () => {
+    let auryEmissionRates = [
+      { minimumStakingPeriod: new anchor.BN(1), rate: new anchor.BN(10) },
+      { minimumStakingPeriod: new anchor.BN(10), rate: new anchor.BN(20) },
+    ];
+    let auryEmissionFund = new anchor.BN(1000);
+
+    await program.rpc.updateAuryEmissionRates(stakingBump, auryEmissionRates, {
+      accounts: {
+        stakingAccount: stakingPubkey,
+        admin: provider.wallet.publicKey,
+      },
+    });
+
+    const oldAuryVaultBalance = await getTokenBalance(auryVaultPubkey);
+
+    await program.rpc.fundAuryEmission(
+      stakingBump,
+      auryVaultBump,
+      auryEmissionFund,
+      {
+        accounts: {
+          stakingAccount: stakingPubkey,
+          auryMint: auryMintPubkey,
+          auryVault: auryVaultPubkey,
+          auryFrom: userAuryTokenAccount,
+          admin: provider.wallet.publicKey,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        },
+      }
+    );
+
+    const stakingAccount = await program.account.stakingAccount.fetch(
+      stakingPubkey
+    );
+    assert.equal(stakingAccount.auryEmissionRates.length, 2);
+    assert.equal(stakingAccount.auryEmissionRates[1].rate.toNumber(), 20);
+    assert.equal(
+      stakingAccount.auryEmissionPool.toNumber(),
+      auryEmissionFund.toNumber()
+    );
+    assert.equal(
+      await getTokenBalance(auryVaultPubkey),
+      oldAuryVaultBalance + auryEmissionFund.toNumber()
+    );
+
+    // the staking flow below runs without emission
+    await program.rpc.updateAuryEmissionRates(stakingBump, [], {
+      accounts: {
+        stakingAccount: stakingPubkey,
+        admin: provider.wallet.publicKey,
+      },
+    });
+  });
+
   it("Update fake authorized creator", async () => {
     await program.rpc.updateAuthorizedCreator(
       stakingBump,
@@ -540,32 +985,127 @@ describe("nft-staking", () => {
     );
   });
 
-  it("Stake failed with mis-match NFT", async () => {
-    // UserStakingCounterAccount pda
-    [userStakingCounterPubkey, userStakingCounterBump] =
-      await anchor.web3.PublicKey.findProgramAddress(
-        [provider.wallet.publicKey.toBuffer()],
-        program.programId
-      );
-
-    // UserStakingAccount pda
-    [userStakingPubkey, userStakingBump] =
-      await anchor.web3.PublicKey.findProgramAddress(
-        [
-          Buffer.from(
-            anchor.utils.bytes.utf8.encode(
-              new anchor.BN(userStakingIndex).toString()
-            )
-          ),
-          provider.wallet.publicKey.toBuffer(),
-        ],
-        program.programId
-      );
+  it("Add/Remove authorized creators", async () => {
+    let newAuthorizedCreators = [
+      Keypair.generate().publicKey,
+      Keypair.generate().publicKey,
+    ];
 
-    // nftVaultBumps
-    let nftVaultBumps = Buffer.from([nftVaultBump[0]]);
+    // Add
+    await program.rpc.addAuthorizedCreators(stakingBump, newAuthorizedCreators, {
+      accounts: {
+        stakingAccount: stakingPubkey,
+        admin: provider.wallet.publicKey,
+      },
+    });
 
-    // Remaining accounts - mint(readonly), metadata(readonly), tokenAccount(writable), vault(writable)
+    const stakingAccount0 = await program.account.stakingAccount.fetch(
+      stakingPubkey
+    );
+    assert.equal(
+      stakingAccount0.authorizedCreators.toString(),
+      newAuthorizedCreators.toString()
+    );
+
+    // Remove
+    await program.rpc.removeAuthorizedCreators(
+      stakingBump,
+      newAuthorizedCreators,
+      {
+        accounts: {
+          stakingAccount: stakingPubkey,
+          admin: provider.wallet.publicKey,
+        },
+      }
+    );
+
+    const stakingAccount1 = await program.account.stakingAccount.fetch(
+      stakingPubkey
+    );
+    assert.equal(stakingAccount1.authorizedCreators.toString(), [].toString());
+  });
+
+  it("Add/Remove blocked mints", async () => {
+    // Add - reason 1: reported stolen
+    await program.rpc.addBlockedMints(
+      stakingBump,
+      mintBlocklistBump,
+      [{ nftMint: nftMintPubkey[0], reason: 1 }],
+      {
+        accounts: {
+          stakingAccount: stakingPubkey,
+          mintBlocklistAccount: mintBlocklistPubkey,
+          admin: provider.wallet.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+          rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        },
+      }
+    );
+
+    const mintBlocklistAccount0 =
+      await program.account.mintBlocklistAccount.fetch(mintBlocklistPubkey);
+    assert.deepEqual(
+      mintBlocklistAccount0.blockedMints.map((blockedMint) => ({
+        nftMint: blockedMint.nftMint.toBase58(),
+        reason: blockedMint.reason,
+      })),
+      [{ nftMint: nftMintPubkey[0].toBase58(), reason: 1 }]
+    );
+
+    // Remove
+    await program.rpc.removeBlockedMints(
+      stakingBump,
+      mintBlocklistBump,
+      [nftMintPubkey[0]],
+      {
+        accounts: {
+          stakingAccount: stakingPubkey,
+          mintBlocklistAccount: mintBlocklistPubkey,
+          admin: provider.wallet.publicKey,
+        },
+      }
+    );
+
+    const mintBlocklistAccount1 =
+      await program.account.mintBlocklistAccount.fetch(mintBlocklistPubkey);
+    assert.equal(mintBlocklistAccount1.blockedMints.length, 0);
+  });
+
+  it("Stake failed with mis-match NFT", async () => {
+    // UserStakingCounterAccount pda
+    [userStakingCounterPubkey, userStakingCounterBump] =
+      await anchor.web3.PublicKey.findProgramAddress(
+        [provider.wallet.publicKey.toBuffer()],
+        program.programId
+      );
+
+    // WalletPositionsAccount pda
+    [walletPositionsPubkey] = await anchor.web3.PublicKey.findProgramAddress(
+      [
+        Buffer.from(anchor.utils.bytes.utf8.encode("wallet_positions")),
+        provider.wallet.publicKey.toBuffer(),
+      ],
+      program.programId
+    );
+
+    // UserStakingAccount pda
+    [userStakingPubkey, userStakingBump] =
+      await anchor.web3.PublicKey.findProgramAddress(
+        [
+          Buffer.from(
+            anchor.utils.bytes.utf8.encode(
+              new anchor.BN(userStakingIndex).toString()
+            )
+          ),
+          provider.wallet.publicKey.toBuffer(),
+        ],
+        program.programId
+      );
+
+    // nftVaultBumps
+    let nftVaultBumps = Buffer.from([nftVaultBump[0]]);
+
+    // Remaining accounts - mint(readonly), metadata(readonly), tokenAccount(writable), vault(writable), aurorianRegistry(readonly), edition(readonly), aurorianStats(readonly), stakeRecord(writable)
     let remainingAccounts = [
       {
         pubkey: nftMintPubkey[0],
@@ -587,6 +1127,26 @@ describe("nft-staking", () => {
         isWritable: true,
         isSigner: false,
       },
+      {
+        pubkey: nftRegistryPubkey[0],
+        isWritable: false,
+        isSigner: false,
+      },
+      {
+        pubkey: nftEditionPubkey[0],
+        isWritable: false,
+        isSigner: false,
+      },
+      {
+        pubkey: nftStatsPubkey[0],
+        isWritable: false,
+        isSigner: false,
+      },
+      {
+        pubkey: nftStakeRecordPubkey[0],
+        isWritable: true,
+        isSigner: false,
+      },
     ];
 
     await assert.rejects(
@@ -596,12 +1156,18 @@ describe("nft-staking", () => {
           stakingBump,
           userStakingCounterBump,
           userStakingBump,
+          eligibilityRulesBump,
+          mintBlocklistBump,
           {
             accounts: {
               nftFromAuthority: provider.wallet.publicKey,
               stakingAccount: stakingPubkey,
               userStakingCounterAccount: userStakingCounterPubkey,
               userStakingAccount: userStakingPubkey,
+              walletPositionsAccount: walletPositionsPubkey,
+              eligibilityRulesAccount: eligibilityRulesPubkey,
+              mintBlocklistAccount: mintBlocklistPubkey,
+              tokenMetadataProgram: METADATA_PROGRAM_ID,
               systemProgram: anchor.web3.SystemProgram.programId,
               tokenProgram: TOKEN_PROGRAM_ID,
               rent: anchor.web3.SYSVAR_RENT_PUBKEY,
@@ -638,7 +1204,7 @@ describe("nft-staking", () => {
     // nftVaultBumps
     let nftVaultBumps = Buffer.from([nftVaultBump[0]]);
 
-    // Remaining accounts - mint(readonly), metadata(readonly), tokenAccount(writable), vault(writable)
+    // Remaining accounts - mint(readonly), metadata(readonly), tokenAccount(writable), vault(writable), aurorianRegistry(readonly), edition(readonly), aurorianStats(readonly), stakeRecord(writable)
     let remainingAccounts = [
       {
         pubkey: nftMintPubkey[0],
@@ -660,6 +1226,26 @@ describe("nft-staking", () => {
         isWritable: true,
         isSigner: false,
       },
+      {
+        pubkey: nftRegistryPubkey[0],
+        isWritable: false,
+        isSigner: false,
+      },
+      {
+        pubkey: nftEditionPubkey[0],
+        isWritable: false,
+        isSigner: false,
+      },
+      {
+        pubkey: nftStatsPubkey[0],
+        isWritable: false,
+        isSigner: false,
+      },
+      {
+        pubkey: nftStakeRecordPubkey[0],
+        isWritable: true,
+        isSigner: false,
+      },
     ];
 
     await assert.rejects(
@@ -669,12 +1255,18 @@ describe("nft-staking", () => {
           stakingBump,
           userStakingCounterBump,
           userStakingBump,
+          eligibilityRulesBump,
+          mintBlocklistBump,
           {
             accounts: {
               nftFromAuthority: provider.wallet.publicKey,
               stakingAccount: stakingPubkey,
               userStakingCounterAccount: userStakingCounterPubkey,
               userStakingAccount: userStakingPubkey,
+              walletPositionsAccount: walletPositionsPubkey,
+              eligibilityRulesAccount: eligibilityRulesPubkey,
+              mintBlocklistAccount: mintBlocklistPubkey,
+              tokenMetadataProgram: METADATA_PROGRAM_ID,
               systemProgram: anchor.web3.SystemProgram.programId,
               tokenProgram: TOKEN_PROGRAM_ID,
               rent: anchor.web3.SYSVAR_RENT_PUBKEY,
@@ -698,7 +1290,7 @@ describe("nft-staking", () => {
     // nftVaultBumps
     let nftVaultBumps = Buffer.from([nftVaultBump[0], nftVaultBump[1]]);
 
-    // Remaining accounts - mint(readonly), metadata(readonly), tokenAccount(writable), vault(writable)
+    // Remaining accounts - mint(readonly), metadata(readonly), tokenAccount(writable), vault(writable), aurorianRegistry(readonly), edition(readonly), aurorianStats(readonly), stakeRecord(writable)
     let remainingAccounts = [
       {
         pubkey: nftMintPubkey[0],
@@ -720,6 +1312,26 @@ describe("nft-staking", () => {
         isWritable: true,
         isSigner: false,
       },
+      {
+        pubkey: nftRegistryPubkey[0],
+        isWritable: false,
+        isSigner: false,
+      },
+      {
+        pubkey: nftEditionPubkey[0],
+        isWritable: false,
+        isSigner: false,
+      },
+      {
+        pubkey: nftStatsPubkey[0],
+        isWritable: false,
+        isSigner: false,
+      },
+      {
+        pubkey: nftStakeRecordPubkey[0],
+        isWritable: true,
+        isSigner: false,
+      },
       {
         pubkey: nftMintPubkey[1],
         isWritable: false,
@@ -740,6 +1352,26 @@ describe("nft-staking", () => {
         isWritable: true,
         isSigner: false,
       },
+      {
+        pubkey: nftRegistryPubkey[1],
+        isWritable: false,
+        isSigner: false,
+      },
+      {
+        pubkey: nftEditionPubkey[1],
+        isWritable: false,
+        isSigner: false,
+      },
+      {
+        pubkey: nftStatsPubkey[1],
+        isWritable: false,
+        isSigner: false,
+      },
+      {
+        pubkey: nftStakeRecordPubkey[1],
+        isWritable: true,
+        isSigner: false,
+      },
     ];
 
     await program.rpc.stake(
@@ -747,12 +1379,18 @@ describe("nft-staking", () => {
       stakingBump,
       userStakingCounterBump,
       userStakingBump,
+      eligibilityRulesBump,
+      mintBlocklistBump,
       {
         accounts: {
           nftFromAuthority: provider.wallet.publicKey,
           stakingAccount: stakingPubkey,
           userStakingCounterAccount: userStakingCounterPubkey,
           userStakingAccount: userStakingPubkey,
+          walletPositionsAccount: walletPositionsPubkey,
+          eligibilityRulesAccount: eligibilityRulesPubkey,
+          mintBlocklistAccount: mintBlocklistPubkey,
+          tokenMetadataProgram: METADATA_PROGRAM_ID,
           systemProgram: anchor.web3.SystemProgram.programId,
           tokenProgram: TOKEN_PROGRAM_ID,
           rent: anchor.web3.SYSVAR_RENT_PUBKEY,
@@ -775,22 +1413,47 @@ describe("nft-staking", () => {
       userStakingAccount.nftMintKeys.toString(),
       [nftMintPubkey[0], nftMintPubkey[1]].toString()
     );
+
+    // metadata snapshot
+    assert.equal(userStakingAccount.stakedNfts[0].name, "Helios: #1");
+    assert.equal(userStakingAccount.stakedNfts[0].symbol, "");
+    assert.equal(
+      userStakingAccount.stakedNfts[0].primaryCreator.toString(),
+      provider.wallet.publicKey.toString()
+    );
+    assert.equal(userStakingAccount.stakedNfts[0].collection, null);
   });
 
-  it("Stake success with match NFT - 1, 2, 3", async () => {
-    // nft balance of user
-    assert.equal(await getTokenBalance(userNFTTokenAccount[1]), 1);
-    assert.equal(await getTokenBalance(userNFTTokenAccount[2]), 3);
-    assert.equal(await getTokenBalance(userNFTTokenAccount[3]), 4);
+  it("Migrate user staking", async () => {
+    const before = await provider.connection.getAccountInfo(userStakingPubkey);
+
+    // an account already in the current layout is left untouched
+    await program.rpc.migrateUserStaking(userStakingIndex, userStakingBump, {
+      accounts: {
+        nftFromAuthority: provider.wallet.publicKey,
+        userStakingAccount: userStakingPubkey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      },
+    });
+
+    const after = await provider.connection.getAccountInfo(userStakingPubkey);
+    assert.equal(after.data.length, before.data.length);
+    assert.equal(after.lamports, before.lamports);
+
+    const userStakingAccount = await program.account.userStakingAccount.fetch(
+      userStakingPubkey
+    );
+    assert.equal(
+      userStakingAccount.nftMintKeys.toString(),
+      [nftMintPubkey[0], nftMintPubkey[1]].toString()
+    );
+  });
 
+  it("Stake failed with already staked NFT - 1", async () => {
     // nftVaultBumps
-    let nftVaultBumps = Buffer.from([
-      nftVaultBump[1],
-      nftVaultBump[2],
-      nftVaultBump[3],
-    ]);
+    let nftVaultBumps = Buffer.from([nftVaultBump[1]]);
 
-    // Remaining accounts - mint(readonly), metadata(readonly), tokenAccount(writable), vault(writable)
+    // Remaining accounts - mint(readonly), metadata(readonly), tokenAccount(writable), vault(writable), aurorianRegistry(readonly), edition(readonly), aurorianStats(readonly), stakeRecord(writable)
     let remainingAccounts = [
       {
         pubkey: nftMintPubkey[1],
@@ -812,6 +1475,88 @@ describe("nft-staking", () => {
         isWritable: true,
         isSigner: false,
       },
+      {
+        pubkey: nftRegistryPubkey[1],
+        isWritable: false,
+        isSigner: false,
+      },
+      {
+        pubkey: nftEditionPubkey[1],
+        isWritable: false,
+        isSigner: false,
+      },
+      {
+        pubkey: nftStatsPubkey[1],
+        isWritable: false,
+        isSigner: false,
+      },
+      {
+        pubkey: nftStakeRecordPubkey[1],
+        isWritable: true,
+        isSigner: false,
+      },
+    ];
+
+    await assert.rejects(
+      async () => {
+        await program.rpc.stake(
+          nftVaultBumps,
+          stakingBump,
+          userStakingCounterBump,
+          userStakingBump,
+          eligibilityRulesBump,
+          mintBlocklistBump,
+          {
+            accounts: {
+              nftFromAuthority: provider.wallet.publicKey,
+              stakingAccount: stakingPubkey,
+              userStakingCounterAccount: userStakingCounterPubkey,
+              userStakingAccount: userStakingPubkey,
+              walletPositionsAccount: walletPositionsPubkey,
+              eligibilityRulesAccount: eligibilityRulesPubkey,
+              mintBlocklistAccount: mintBlocklistPubkey,
+              tokenMetadataProgram: METADATA_PROGRAM_ID,
+              systemProgram: anchor.web3.SystemProgram.programId,
+              tokenProgram: TOKEN_PROGRAM_ID,
+              rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+            },
+            remainingAccounts,
+          }
+        );
+      },
+      {
+        code: 6053,
+        // message: '6053: Nft already staked',
+      }
+    );
+
+    // the stake record points to the position holding the NFT
+    const stakeRecordAccount = await program.account.stakeRecordAccount.fetch(
+      nftStakeRecordPubkey[1]
+    );
+    assert.equal(
+      stakeRecordAccount.wallet.toString(),
+      provider.wallet.publicKey.toString()
+    );
+    assert.equal(stakeRecordAccount.userStakingIndex, userStakingIndex);
+    assert.equal(
+      stakeRecordAccount.vault.toString(),
+      nftVaultPubkey[1].toString()
+    );
+  });
+
+  it("Stake success with match NFT - 2, 3", async () => {
+    // 0.1.0 staked 1, 2 and 3 in one transaction, 2 is the maximum since 0.2.0
+
+    // nft balance of user
+    assert.equal(await getTokenBalance(userNFTTokenAccount[2]), 3);
+    assert.equal(await getTokenBalance(userNFTTokenAccount[3]), 4);
+
+    // nftVaultBumps
+    let nftVaultBumps = Buffer.from([nftVaultBump[2], nftVaultBump[3]]);
+
+    // Remaining accounts - mint(readonly), metadata(readonly), tokenAccount(writable), vault(writable), aurorianRegistry(readonly), edition(readonly), aurorianStats(readonly), stakeRecord(writable)
+    let remainingAccounts = [
       {
         pubkey: nftMintPubkey[2],
         isWritable: false,
@@ -832,6 +1577,26 @@ describe("nft-staking", () => {
         isWritable: true,
         isSigner: false,
       },
+      {
+        pubkey: nftRegistryPubkey[2],
+        isWritable: false,
+        isSigner: false,
+      },
+      {
+        pubkey: nftEditionPubkey[2],
+        isWritable: false,
+        isSigner: false,
+      },
+      {
+        pubkey: nftStatsPubkey[2],
+        isWritable: false,
+        isSigner: false,
+      },
+      {
+        pubkey: nftStakeRecordPubkey[2],
+        isWritable: true,
+        isSigner: false,
+      },
       {
         pubkey: nftMintPubkey[3],
         isWritable: false,
@@ -852,6 +1617,26 @@ describe("nft-staking", () => {
         isWritable: true,
         isSigner: false,
       },
+      {
+        pubkey: nftRegistryPubkey[3],
+        isWritable: false,
+        isSigner: false,
+      },
+      {
+        pubkey: nftEditionPubkey[3],
+        isWritable: false,
+        isSigner: false,
+      },
+      {
+        pubkey: nftStatsPubkey[3],
+        isWritable: false,
+        isSigner: false,
+      },
+      {
+        pubkey: nftStakeRecordPubkey[3],
+        isWritable: true,
+        isSigner: false,
+      },
     ];
 
     await program.rpc.stake(
@@ -859,12 +1644,18 @@ describe("nft-staking", () => {
       stakingBump,
       userStakingCounterBump,
       userStakingBump,
+      eligibilityRulesBump,
+      mintBlocklistBump,
       {
         accounts: {
           nftFromAuthority: provider.wallet.publicKey,
           stakingAccount: stakingPubkey,
           userStakingCounterAccount: userStakingCounterPubkey,
           userStakingAccount: userStakingPubkey,
+          walletPositionsAccount: walletPositionsPubkey,
+          eligibilityRulesAccount: eligibilityRulesPubkey,
+          mintBlocklistAccount: mintBlocklistPubkey,
+          tokenMetadataProgram: METADATA_PROGRAM_ID,
           systemProgram: anchor.web3.SystemProgram.programId,
           tokenProgram: TOKEN_PROGRAM_ID,
           rent: anchor.web3.SYSVAR_RENT_PUBKEY,
@@ -874,8 +1665,6 @@ describe("nft-staking", () => {
     );
 
     // nft balance of user and program
-    assert.equal(await getTokenBalance(userNFTTokenAccount[1]), 0);
-    assert.equal(await getTokenBalance(nftVaultPubkey[1]), 2);
     assert.equal(await getTokenBalance(userNFTTokenAccount[2]), 2);
     assert.equal(await getTokenBalance(nftVaultPubkey[2]), 1);
     assert.equal(await getTokenBalance(userNFTTokenAccount[3]), 3);
@@ -890,14 +1679,53 @@ describe("nft-staking", () => {
       [
         nftMintPubkey[0],
         nftMintPubkey[1],
-        nftMintPubkey[1],
         nftMintPubkey[2],
         nftMintPubkey[3],
       ].toString()
     );
   });
 
-  it("Unstake failed for not locked staking", async () => {
+  it("Set destination", async () => {
+    let config = {
+      name: "Helios Peaks",
+      allowedDurations: [userStakingPeriod, maximumStakingPeriod],
+      entryFee: new anchor.BN(entryFee),
+      partyRules: { minPartySize: 1, maxPartySize: 10, constraints: [] },
+      lootTableId: 1,
+      capacity: 0,
+    };
+
+    await program.rpc.setDestination(
+      stakingBump,
+      destinationId,
+      destinationBump,
+      config,
+      {
+        accounts: {
+          stakingAccount: stakingPubkey,
+          destinationAccount: destinationPubkey,
+          admin: provider.wallet.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+          rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        },
+      }
+    );
+
+    const destinationAccount = await program.account.destinationAccount.fetch(
+      destinationPubkey
+    );
+    assert.equal(destinationAccount.destinationId, destinationId);
+    assert.equal(destinationAccount.config.name, config.name);
+    assert.equal(
+      destinationAccount.config.allowedDurations.toString(),
+      [userStakingPeriod, maximumStakingPeriod].toString()
+    );
+    assert.equal(destinationAccount.config.entryFee.toNumber(), entryFee);
+    assert.equal(destinationAccount.config.lootTableId, 1);
+    assert.equal(destinationAccount.activePositions, 0);
+  });
+
+  it("Unstake failed for not locked staking", async () => {
     await assert.rejects(
       async () => {
         await program.rpc.unstake(
@@ -909,6 +1737,10 @@ describe("nft-staking", () => {
               nftToAuthority: provider.wallet.publicKey,
               stakingAccount: stakingPubkey,
               userStakingAccount: userStakingPubkey,
+              walletPositionsAccount: walletPositionsPubkey,
+              destinationAccount: destinationPubkey,
+              tokenMetadataProgram: METADATA_PROGRAM_ID,
+              systemProgram: anchor.web3.SystemProgram.programId,
               tokenProgram: TOKEN_PROGRAM_ID,
             },
           }
@@ -921,6 +1753,80 @@ describe("nft-staking", () => {
     );
   });
 
+  it("Set party rules", async () => {
+    await program.rpc.setPartyRules(stakingBump, partyRulesBump, partyRules, {
+      accounts: {
+        stakingAccount: stakingPubkey,
+        partyRulesAccount: partyRulesPubkey,
+        admin: provider.wallet.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+        rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+      },
+    });
+
+    const partyRulesAccount = await program.account.partyRulesAccount.fetch(
+      partyRulesPubkey
+    );
+    assert.equal(partyRulesAccount.partyRules.minPartySize, 1);
+    assert.equal(partyRulesAccount.partyRules.maxPartySize, 10);
+    assert.equal(
+      partyRulesAccount.partyRules.constraints[0].member.namePrefix.prefix,
+      "Helios"
+    );
+
+    // min party size above max
+    await assert.rejects(
+      async () => {
+        await program.rpc.setPartyRules(
+          stakingBump,
+          partyRulesBump,
+          { minPartySize: 5, maxPartySize: 4, constraints: [] },
+          {
+            accounts: {
+              stakingAccount: stakingPubkey,
+              partyRulesAccount: partyRulesPubkey,
+              admin: provider.wallet.publicKey,
+              systemProgram: anchor.web3.SystemProgram.programId,
+              rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+            },
+          }
+        );
+      },
+      {
+        code: 6055,
+        // message: '6055: Invalid party rules',
+      }
+    );
+  });
+
+  it("Set synergies", async () => {
+    // two Aurorians of rarity 1 or more boost the emission by 10%
+    let synergies = [
+      {
+        member: { minimumRarity: { rarity: 1 } },
+        count: 2,
+        bonus: 1000,
+      },
+    ];
+
+    await program.rpc.setSynergies(stakingBump, synergiesBump, synergies, {
+      accounts: {
+        stakingAccount: stakingPubkey,
+        synergiesAccount: synergiesPubkey,
+        admin: provider.wallet.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+        rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+      },
+    });
+
+    const synergiesAccount = await program.account.synergiesAccount.fetch(
+      synergiesPubkey
+    );
+    assert.equal(synergiesAccount.synergies.length, 1);
+    assert.equal(synergiesAccount.synergies[0].count, 2);
+    assert.equal(synergiesAccount.synergies[0].bonus, 1000);
+  });
+
   it("Lock stake failed with invalid staking period", async () => {
     let invalidStakingPeriod = maximumStakingPeriod.add(minimumStakingPeriod);
 
@@ -939,9 +1845,14 @@ describe("nft-staking", () => {
               stakingAccount: stakingPubkey,
               userStakingCounterAccount: userStakingCounterPubkey,
               userStakingAccount: userStakingPubkey,
+              walletPositionsAccount: walletPositionsPubkey,
+              partyRulesAccount: partyRulesPubkey,
+              synergiesAccount: synergiesPubkey,
+              destinationAccount: destinationPubkey,
               auryMint: auryMintPubkey,
               auryVault: auryVaultPubkey,
               auryFrom: userAuryTokenAccount,
+              systemProgram: anchor.web3.SystemProgram.programId,
               tokenProgram: TOKEN_PROGRAM_ID,
             },
           }
@@ -954,6 +1865,126 @@ describe("nft-staking", () => {
     );
   });
 
+  it("Lock stake failed with invalid party size", async () => {
+    // the party has 4 Aurorians
+    await program.rpc.setPartyRules(
+      stakingBump,
+      partyRulesBump,
+      { minPartySize: 5, maxPartySize: 10, constraints: [] },
+      {
+        accounts: {
+          stakingAccount: stakingPubkey,
+          partyRulesAccount: partyRulesPubkey,
+          admin: provider.wallet.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+          rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        },
+      }
+    );
+
+    await assert.rejects(
+      async () => {
+        await program.rpc.lockStake(
+          stakingBump,
+          userStakingCounterBump,
+          userStakingBump,
+          auryVaultBump,
+          userStakingPeriod,
+          auryDepositAmount,
+          {
+            accounts: {
+              nftFromAuthority: provider.wallet.publicKey,
+              stakingAccount: stakingPubkey,
+              userStakingCounterAccount: userStakingCounterPubkey,
+              userStakingAccount: userStakingPubkey,
+              walletPositionsAccount: walletPositionsPubkey,
+              partyRulesAccount: partyRulesPubkey,
+              synergiesAccount: synergiesPubkey,
+              destinationAccount: destinationPubkey,
+              auryMint: auryMintPubkey,
+              auryVault: auryVaultPubkey,
+              auryFrom: userAuryTokenAccount,
+              systemProgram: anchor.web3.SystemProgram.programId,
+              tokenProgram: TOKEN_PROGRAM_ID,
+            },
+          }
+        );
+      },
+      {
+        code: 6056,
+        // message: '6056: Invalid party size',
+      }
+    );
+
+    await program.rpc.setPartyRules(stakingBump, partyRulesBump, partyRules, {
+      accounts: {
+        stakingAccount: stakingPubkey,
+        partyRulesAccount: partyRulesPubkey,
+        admin: provider.wallet.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+        rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+      },
+    });
+  });
+
+  it("Lock stake failed with full expedition", async () => {
+    // the party has 4 Aurorians
+    await program.rpc.updateCapacity(stakingBump, 1, 3, 0, {
+      accounts: {
+        stakingAccount: stakingPubkey,
+        admin: provider.wallet.publicKey,
+      },
+    });
+
+    await assert.rejects(
+      async () => {
+        await program.rpc.lockStake(
+          stakingBump,
+          userStakingCounterBump,
+          userStakingBump,
+          auryVaultBump,
+          userStakingPeriod,
+          auryDepositAmount,
+          {
+            accounts: {
+              nftFromAuthority: provider.wallet.publicKey,
+              stakingAccount: stakingPubkey,
+              userStakingCounterAccount: userStakingCounterPubkey,
+              userStakingAccount: userStakingPubkey,
+              walletPositionsAccount: walletPositionsPubkey,
+              partyRulesAccount: partyRulesPubkey,
+              synergiesAccount: synergiesPubkey,
+              destinationAccount: destinationPubkey,
+              auryMint: auryMintPubkey,
+              auryVault: auryVaultPubkey,
+              auryFrom: userAuryTokenAccount,
+              systemProgram: anchor.web3.SystemProgram.programId,
+              tokenProgram: TOKEN_PROGRAM_ID,
+            },
+          }
+        );
+      },
+      {
+        code: 6062,
+        // message: '6062: Expedition full',
+      }
+    );
+
+    await program.rpc.updateCapacity(stakingBump, 1, 10, 0, {
+      accounts: {
+        stakingAccount: stakingPubkey,
+        admin: provider.wallet.publicKey,
+      },
+    });
+
+    const stakingAccount = await program.account.stakingAccount.fetch(
+      stakingPubkey
+    );
+    assert.equal(stakingAccount.maxActivePositions, 1);
+    assert.equal(stakingAccount.maxActiveNfts, 10);
+    assert.equal(stakingAccount.maxWalletPositions, 0);
+  });
+
   it("Add winner failed for not locked staking", async () => {
     // Remaining accounts - mint(readonly), userStakingAccount(writable)
     let remainingAccounts = [
@@ -979,11 +2010,13 @@ describe("nft-staking", () => {
         await program.rpc.addWinner(
           //@ts-ignore
           stakingBump,
+          mintBlocklistBump,
           winnerStakingIndexes,
           winners,
           {
             accounts: {
               stakingAccount: stakingPubkey,
+              mintBlocklistAccount: mintBlocklistPubkey,
               admin: provider.wallet.publicKey,
             },
             remainingAccounts,
@@ -1020,6 +2053,7 @@ describe("nft-staking", () => {
           // @ts-ignore
           stakingBump,
           auryVaultBump,
+          mintBlocklistBump,
           winnerStakingIndexes,
           winners,
           auryAmounts,
@@ -1029,6 +2063,7 @@ describe("nft-staking", () => {
               auryMint: auryMintPubkey,
               auryVault: auryVaultPubkey,
               auryFrom: userAuryTokenAccount,
+              mintBlocklistAccount: mintBlocklistPubkey,
               admin: provider.wallet.publicKey,
               tokenProgram: TOKEN_PROGRAM_ID,
             },
@@ -1060,9 +2095,14 @@ describe("nft-staking", () => {
           stakingAccount: stakingPubkey,
           userStakingCounterAccount: userStakingCounterPubkey,
           userStakingAccount: userStakingPubkey,
+          walletPositionsAccount: walletPositionsPubkey,
+          partyRulesAccount: partyRulesPubkey,
+          synergiesAccount: synergiesPubkey,
+          destinationAccount: destinationPubkey,
           auryMint: auryMintPubkey,
           auryVault: auryVaultPubkey,
           auryFrom: userAuryTokenAccount,
+          systemProgram: anchor.web3.SystemProgram.programId,
           tokenProgram: TOKEN_PROGRAM_ID,
         },
       }
@@ -1094,9 +2134,136 @@ describe("nft-staking", () => {
       userStakingAccount.auryDeposit.toNumber(),
       auryDepositAmount.toNumber()
     );
+    // rarities 0, 1, 2, 0 match the synergy
+    assert.equal(userStakingAccount.synergyMultiplier.toNumber(), 11000);
+    assert.equal(userStakingAccount.destinationId, destinationId);
+
+    // the terms the expedition settles under
+    assert.equal(userStakingAccount.config.version, 1);
+    assert.equal(
+      userStakingAccount.config.minimumStakingPeriod.toNumber(),
+      minimumStakingPeriod.toNumber()
+    );
+    assert.equal(
+      userStakingAccount.config.maximumStakingPeriod.toNumber(),
+      maximumStakingPeriod.toNumber()
+    );
+    assert.equal(
+      userStakingAccount.config.allowedDurations.toString(),
+      [userStakingPeriod, maximumStakingPeriod].toString()
+    );
+    assert.equal(userStakingAccount.config.entryFee.toNumber(), entryFee);
+    assert.equal(userStakingAccount.config.lootTableId, 1);
+
+    const destinationAccount = await program.account.destinationAccount.fetch(
+      destinationPubkey
+    );
+    assert.equal(destinationAccount.activePositions, 1);
+
+    const stakingAccount = await program.account.stakingAccount.fetch(
+      stakingPubkey
+    );
+    assert.equal(stakingAccount.activePositions, 1);
+    assert.equal(stakingAccount.activeNfts, 4);
     assert.equal(
       await getTokenBalance(auryVaultPubkey),
-      auryDepositAmount.addn(oldAuryVaultBalance)
+      auryDepositAmount.addn(oldAuryVaultBalance + entryFee)
+    );
+  });
+
+  it("Extend expedition", async () => {
+    let oldAuryVaultBalance = await getTokenBalance(auryVaultPubkey);
+
+    // the whole expedition must last one of the destination's durations
+    await assert.rejects(
+      async () => {
+        await program.rpc.extendExpedition(
+          stakingBump,
+          userStakingIndex,
+          userStakingBump,
+          auryVaultBump,
+          minimumStakingPeriod,
+          new anchor.BN(0),
+          {
+            accounts: {
+              nftFromAuthority: provider.wallet.publicKey,
+              stakingAccount: stakingPubkey,
+              userStakingAccount: userStakingPubkey,
+              auryMint: auryMintPubkey,
+              auryVault: auryVaultPubkey,
+              auryFrom: userAuryTokenAccount,
+              tokenProgram: TOKEN_PROGRAM_ID,
+            },
+          }
+        );
+      },
+      {
+        code: 6015,
+        // message: '6015: Invalid staking period',
+      }
+    );
+
+    await program.rpc.extendExpedition(
+      stakingBump,
+      userStakingIndex,
+      userStakingBump,
+      auryVaultBump,
+      userStakingPeriod,
+      new anchor.BN(0),
+      {
+        accounts: {
+          nftFromAuthority: provider.wallet.publicKey,
+          stakingAccount: stakingPubkey,
+          userStakingAccount: userStakingPubkey,
+          auryMint: auryMintPubkey,
+          auryVault: auryVaultPubkey,
+          auryFrom: userAuryTokenAccount,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        },
+      }
+    );
+
+    const userStakingAccount = await program.account.userStakingAccount.fetch(
+      userStakingPubkey
+    );
+    assert.equal(
+      userStakingAccount.stakingPeriod.toNumber(),
+      userStakingPeriod.muln(2).toNumber()
+    );
+    assert.equal(
+      userStakingAccount.auryDeposit.toNumber(),
+      auryDepositAmount.toNumber()
+    );
+    // the extension doesn't pay the entry fee again
+    assert.equal(await getTokenBalance(auryVaultPubkey), oldAuryVaultBalance);
+
+    // the extended expedition would exceed the maximum staking period
+    await assert.rejects(
+      async () => {
+        await program.rpc.extendExpedition(
+          stakingBump,
+          userStakingIndex,
+          userStakingBump,
+          auryVaultBump,
+          userStakingPeriod,
+          new anchor.BN(0),
+          {
+            accounts: {
+              nftFromAuthority: provider.wallet.publicKey,
+              stakingAccount: stakingPubkey,
+              userStakingAccount: userStakingPubkey,
+              auryMint: auryMintPubkey,
+              auryVault: auryVaultPubkey,
+              auryFrom: userAuryTokenAccount,
+              tokenProgram: TOKEN_PROGRAM_ID,
+            },
+          }
+        );
+      },
+      {
+        code: 6015,
+        // message: '6015: Invalid staking period',
+      }
     );
   });
 
@@ -1112,6 +2279,10 @@ describe("nft-staking", () => {
               nftToAuthority: provider.wallet.publicKey,
               stakingAccount: stakingPubkey,
               userStakingAccount: userStakingPubkey,
+              walletPositionsAccount: walletPositionsPubkey,
+              destinationAccount: destinationPubkey,
+              tokenMetadataProgram: METADATA_PROGRAM_ID,
+              systemProgram: anchor.web3.SystemProgram.programId,
               tokenProgram: TOKEN_PROGRAM_ID,
             },
           }
@@ -1149,11 +2320,13 @@ describe("nft-staking", () => {
         await program.rpc.addWinner(
           // @ts-ignore
           stakingBump,
+          mintBlocklistBump,
           winnerStakingIndexes,
           winners,
           {
             accounts: {
               stakingAccount: stakingPubkey,
+              mintBlocklistAccount: mintBlocklistPubkey,
               admin: provider.wallet.publicKey,
             },
             remainingAccounts,
@@ -1212,9 +2385,10 @@ describe("nft-staking", () => {
     let winners = [winner, winner, winner];
 
     // @ts-ignore
-    await program.rpc.addWinner(stakingBump, winnerStakingIndexes, winners, {
+    await program.rpc.addWinner(stakingBump, mintBlocklistBump, winnerStakingIndexes, winners, {
       accounts: {
         stakingAccount: stakingPubkey,
+        mintBlocklistAccount: mintBlocklistPubkey,
         admin: provider.wallet.publicKey,
       },
       remainingAccounts,
@@ -1270,6 +2444,7 @@ describe("nft-staking", () => {
       // @ts-ignore
       stakingBump,
       auryVaultBump,
+      mintBlocklistBump,
       winnerStakingIndexes,
       winners,
       auryAmounts,
@@ -1279,6 +2454,7 @@ describe("nft-staking", () => {
           auryMint: auryMintPubkey,
           auryVault: auryVaultPubkey,
           auryFrom: userAuryTokenAccount,
+          mintBlocklistAccount: mintBlocklistPubkey,
           admin: provider.wallet.publicKey,
           tokenProgram: TOKEN_PROGRAM_ID,
         },
@@ -1312,6 +2488,10 @@ describe("nft-staking", () => {
               nftToAuthority: provider.wallet.publicKey,
               stakingAccount: stakingPubkey,
               userStakingAccount: userStakingPubkey,
+              walletPositionsAccount: walletPositionsPubkey,
+              destinationAccount: destinationPubkey,
+              tokenMetadataProgram: METADATA_PROGRAM_ID,
+              systemProgram: anchor.web3.SystemProgram.programId,
               tokenProgram: TOKEN_PROGRAM_ID,
             },
           }
@@ -1415,7 +2595,9 @@ describe("nft-staking", () => {
           auryVault: auryVaultPubkey,
           auryTo: userAuryTokenAccount,
           auryToAuthority: provider.wallet.publicKey,
+          stakingAccount: stakingPubkey,
           userStakingAccount: userStakingPubkey,
+          mintBlocklistAccount: mintBlocklistPubkey,
           tokenProgram: TOKEN_PROGRAM_ID,
         },
       }
@@ -1430,12 +2612,60 @@ describe("nft-staking", () => {
     assert.equal(userStakingAccount.claimableAuryAmount.toNumber(), 0);
   });
 
-  it("Unstake success after claim", async () => {
-    await sleep(1000);
+  it("Extend expedition failed after the expedition returned", async () => {
+    const userStakingAccount = await program.account.userStakingAccount.fetch(
+      userStakingPubkey
+    );
+    const returnAt = userStakingAccount.stakingAt
+      .add(userStakingAccount.stakingPeriod)
+      .toNumber();
+    await sleep(Math.max(returnAt + 1 - dayjs().unix(), 0) * 1000);
 
-    // Remaining accounts - mint(writable), vault(writable)
-    let remainingAccounts = [
-      {
+    await assert.rejects(
+      async () => {
+        await program.rpc.extendExpedition(
+          stakingBump,
+          userStakingIndex,
+          userStakingBump,
+          auryVaultBump,
+          minimumStakingPeriod,
+          new anchor.BN(0),
+          {
+            accounts: {
+              nftFromAuthority: provider.wallet.publicKey,
+              stakingAccount: stakingPubkey,
+              userStakingAccount: userStakingPubkey,
+              auryMint: auryMintPubkey,
+              auryVault: auryVaultPubkey,
+              auryFrom: userAuryTokenAccount,
+              tokenProgram: TOKEN_PROGRAM_ID,
+            },
+          }
+        );
+      },
+      {
+        code: 6047,
+        // message: '6047: Expedition finished',
+      }
+    );
+  });
+
+  it("Unstake success after claim", async () => {
+    await sleep(1000);
+
+    // Remaining accounts - mint(readonly), edition(readonly), tokenAccount(writable), vault(writable), aurorianStats(writable), stakeRecord(writable)
+    let remainingAccounts = [
+      {
+        pubkey: nftMintPubkey[0],
+        isWritable: false,
+        isSigner: false,
+      },
+      {
+        pubkey: nftEditionPubkey[0],
+        isWritable: false,
+        isSigner: false,
+      },
+      {
         pubkey: userNFTTokenAccount[0],
         isWritable: true,
         isSigner: false,
@@ -1446,15 +2676,25 @@ describe("nft-staking", () => {
         isSigner: false,
       },
       {
-        pubkey: userNFTTokenAccount[1],
+        pubkey: nftStatsPubkey[0],
         isWritable: true,
         isSigner: false,
       },
       {
-        pubkey: nftVaultPubkey[1],
+        pubkey: nftStakeRecordPubkey[0],
         isWritable: true,
         isSigner: false,
       },
+      {
+        pubkey: nftMintPubkey[1],
+        isWritable: false,
+        isSigner: false,
+      },
+      {
+        pubkey: nftEditionPubkey[1],
+        isWritable: false,
+        isSigner: false,
+      },
       {
         pubkey: userNFTTokenAccount[1],
         isWritable: true,
@@ -1465,6 +2705,26 @@ describe("nft-staking", () => {
         isWritable: true,
         isSigner: false,
       },
+      {
+        pubkey: nftStatsPubkey[1],
+        isWritable: true,
+        isSigner: false,
+      },
+      {
+        pubkey: nftStakeRecordPubkey[1],
+        isWritable: true,
+        isSigner: false,
+      },
+      {
+        pubkey: nftMintPubkey[2],
+        isWritable: false,
+        isSigner: false,
+      },
+      {
+        pubkey: nftEditionPubkey[2],
+        isWritable: false,
+        isSigner: false,
+      },
       {
         pubkey: userNFTTokenAccount[2],
         isWritable: true,
@@ -1475,6 +2735,26 @@ describe("nft-staking", () => {
         isWritable: true,
         isSigner: false,
       },
+      {
+        pubkey: nftStatsPubkey[2],
+        isWritable: true,
+        isSigner: false,
+      },
+      {
+        pubkey: nftStakeRecordPubkey[2],
+        isWritable: true,
+        isSigner: false,
+      },
+      {
+        pubkey: nftMintPubkey[3],
+        isWritable: false,
+        isSigner: false,
+      },
+      {
+        pubkey: nftEditionPubkey[3],
+        isWritable: false,
+        isSigner: false,
+      },
       {
         pubkey: userNFTTokenAccount[3],
         isWritable: true,
@@ -1485,6 +2765,16 @@ describe("nft-staking", () => {
         isWritable: true,
         isSigner: false,
       },
+      {
+        pubkey: nftStatsPubkey[3],
+        isWritable: true,
+        isSigner: false,
+      },
+      {
+        pubkey: nftStakeRecordPubkey[3],
+        isWritable: true,
+        isSigner: false,
+      },
     ];
 
     await program.rpc.unstake(stakingBump, userStakingIndex, userStakingBump, {
@@ -1492,6 +2782,10 @@ describe("nft-staking", () => {
         nftToAuthority: provider.wallet.publicKey,
         stakingAccount: stakingPubkey,
         userStakingAccount: userStakingPubkey,
+        walletPositionsAccount: walletPositionsPubkey,
+        destinationAccount: destinationPubkey,
+        tokenMetadataProgram: METADATA_PROGRAM_ID,
+        systemProgram: anchor.web3.SystemProgram.programId,
         tokenProgram: TOKEN_PROGRAM_ID,
       },
       remainingAccounts,
@@ -1509,6 +2803,41 @@ describe("nft-staking", () => {
         }
       );
     }
+
+    // the expedition is over
+    const stakingAccount = await program.account.stakingAccount.fetch(
+      stakingPubkey
+    );
+    assert.equal(stakingAccount.activePositions, 0);
+    assert.equal(stakingAccount.activeNfts, 0);
+
+    const walletPositionsAccount =
+      await program.account.walletPositionsAccount.fetch(walletPositionsPubkey);
+    assert.equal(walletPositionsAccount.activePositions, 0);
+
+    // the stake records are closed with the unstake
+    for (let i = 0; i < 4; i++) {
+      assert.equal(
+        await provider.connection.getAccountInfo(nftStakeRecordPubkey[i]),
+        null
+      );
+    }
+
+    // expeditions shorter than the xp period don't earn xp
+    const aurorianStatsAccount =
+      await program.account.aurorianStatsAccount.fetch(nftStatsPubkey[0]);
+    assert.equal(
+      aurorianStatsAccount.nftMint.toString(),
+      nftMintPubkey[0].toString()
+    );
+    assert.equal(aurorianStatsAccount.expeditionsCompleted, 1);
+    // locked for the extended expedition
+    assert.equal(
+      aurorianStatsAccount.totalLockedTime.toNumber(),
+      userStakingPeriod.muln(2).toNumber()
+    );
+    assert.equal(aurorianStatsAccount.xp.toNumber(), 0);
+    assert.equal(aurorianStatsAccount.level, 0);
   });
 
   it("Next stake success with empty-authorized-name-starts", async () => {
@@ -1545,7 +2874,7 @@ describe("nft-staking", () => {
     // nftVaultBumps
     let nftVaultBumps = Buffer.from([nftVaultBump[0]]);
 
-    // Remaining accounts - mint(readonly), metadata(readonly), tokenAccount(writable), vault(writable)
+    // Remaining accounts - mint(readonly), metadata(readonly), tokenAccount(writable), vault(writable), aurorianRegistry(readonly), edition(readonly), aurorianStats(readonly), stakeRecord(writable)
     let remainingAccounts = [
       {
         pubkey: nftMintPubkey[0],
@@ -1567,6 +2896,26 @@ describe("nft-staking", () => {
         isWritable: true,
         isSigner: false,
       },
+      {
+        pubkey: nftRegistryPubkey[0],
+        isWritable: false,
+        isSigner: false,
+      },
+      {
+        pubkey: nftEditionPubkey[0],
+        isWritable: false,
+        isSigner: false,
+      },
+      {
+        pubkey: nftStatsPubkey[0],
+        isWritable: false,
+        isSigner: false,
+      },
+      {
+        pubkey: nftStakeRecordPubkey[0],
+        isWritable: true,
+        isSigner: false,
+      },
     ];
 
     await program.rpc.stake(
@@ -1574,12 +2923,18 @@ describe("nft-staking", () => {
       stakingBump,
       userStakingCounterBump,
       nextUserStakingBump,
+      eligibilityRulesBump,
+      mintBlocklistBump,
       {
         accounts: {
           nftFromAuthority: provider.wallet.publicKey,
           stakingAccount: stakingPubkey,
           userStakingCounterAccount: userStakingCounterPubkey,
           userStakingAccount: nextUserStakingPubkey,
+          walletPositionsAccount: walletPositionsPubkey,
+          eligibilityRulesAccount: eligibilityRulesPubkey,
+          mintBlocklistAccount: mintBlocklistPubkey,
+          tokenMetadataProgram: METADATA_PROGRAM_ID,
           systemProgram: anchor.web3.SystemProgram.programId,
           tokenProgram: TOKEN_PROGRAM_ID,
           rent: anchor.web3.SYSVAR_RENT_PUBKEY,
@@ -1601,16 +2956,11 @@ describe("nft-staking", () => {
     );
   });
 
-  it("Stake - maximum size is 4", async () => {
+  it("Stake - maximum size is 2", async () => {
     // nftVaultBumps
-    let nftVaultBumps = Buffer.from([
-      nftVaultBump[1],
-      nftVaultBump[2],
-      nftVaultBump[3],
-      nftVaultBump[4],
-    ]);
+    let nftVaultBumps = Buffer.from([nftVaultBump[1], nftVaultBump[2]]);
 
-    // Remaining accounts - mint(readonly), metadata(readonly), tokenAccount(writable), vault(writable)
+    // Remaining accounts - mint(readonly), metadata(readonly), tokenAccount(writable), vault(writable), aurorianRegistry(readonly), edition(readonly), aurorianStats(readonly), stakeRecord(writable)
     let remainingAccounts = [
       {
         pubkey: nftMintPubkey[1],
@@ -1633,78 +2983,126 @@ describe("nft-staking", () => {
         isSigner: false,
       },
       {
-        pubkey: nftMintPubkey[2],
+        pubkey: nftRegistryPubkey[1],
         isWritable: false,
         isSigner: false,
       },
       {
-        pubkey: nftMetadataPubkey[2],
+        pubkey: nftEditionPubkey[1],
         isWritable: false,
         isSigner: false,
       },
       {
-        pubkey: userNFTTokenAccount[2],
-        isWritable: true,
+        pubkey: nftStatsPubkey[1],
+        isWritable: false,
         isSigner: false,
       },
       {
-        pubkey: nftVaultPubkey[2],
+        pubkey: nftStakeRecordPubkey[1],
         isWritable: true,
         isSigner: false,
       },
       {
-        pubkey: nftMintPubkey[3],
+        pubkey: nftMintPubkey[2],
         isWritable: false,
         isSigner: false,
       },
       {
-        pubkey: nftMetadataPubkey[3],
+        pubkey: nftMetadataPubkey[2],
         isWritable: false,
         isSigner: false,
       },
       {
-        pubkey: userNFTTokenAccount[3],
+        pubkey: userNFTTokenAccount[2],
         isWritable: true,
         isSigner: false,
       },
       {
-        pubkey: nftVaultPubkey[3],
+        pubkey: nftVaultPubkey[2],
         isWritable: true,
         isSigner: false,
       },
       {
-        pubkey: nftMintPubkey[4],
+        pubkey: nftRegistryPubkey[2],
         isWritable: false,
         isSigner: false,
       },
       {
-        pubkey: nftMetadataPubkey[4],
+        pubkey: nftEditionPubkey[2],
         isWritable: false,
         isSigner: false,
       },
       {
-        pubkey: userNFTTokenAccount[4],
-        isWritable: true,
+        pubkey: nftStatsPubkey[2],
+        isWritable: false,
         isSigner: false,
       },
       {
-        pubkey: nftVaultPubkey[4],
+        pubkey: nftStakeRecordPubkey[2],
         isWritable: true,
         isSigner: false,
       },
     ];
 
+    // a third Aurorian no longer fits in the transaction, 0.1.0 took up to 4
+    await assert.rejects(
+      async () => {
+        await program.rpc.stake(
+          Buffer.from([nftVaultBump[1], nftVaultBump[2], nftVaultBump[3]]),
+          stakingBump,
+          userStakingCounterBump,
+          nextUserStakingBump,
+          eligibilityRulesBump,
+          mintBlocklistBump,
+          {
+            accounts: {
+              nftFromAuthority: provider.wallet.publicKey,
+              stakingAccount: stakingPubkey,
+              userStakingCounterAccount: userStakingCounterPubkey,
+              userStakingAccount: nextUserStakingPubkey,
+              walletPositionsAccount: walletPositionsPubkey,
+              eligibilityRulesAccount: eligibilityRulesPubkey,
+              mintBlocklistAccount: mintBlocklistPubkey,
+              tokenMetadataProgram: METADATA_PROGRAM_ID,
+              systemProgram: anchor.web3.SystemProgram.programId,
+              tokenProgram: TOKEN_PROGRAM_ID,
+              rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+            },
+            remainingAccounts: remainingAccounts.concat(
+              [
+                nftMintPubkey[3],
+                nftMetadataPubkey[3],
+                userNFTTokenAccount[3],
+                nftVaultPubkey[3],
+                nftRegistryPubkey[3],
+                nftEditionPubkey[3],
+                nftStatsPubkey[3],
+                nftStakeRecordPubkey[3],
+              ].map((pubkey) => ({ pubkey, isWritable: true, isSigner: false }))
+            ),
+          }
+        );
+      },
+      isTransactionTooLarge
+    );
+
     await program.rpc.stake(
       nftVaultBumps,
       stakingBump,
       userStakingCounterBump,
       nextUserStakingBump,
+      eligibilityRulesBump,
+      mintBlocklistBump,
       {
         accounts: {
           nftFromAuthority: provider.wallet.publicKey,
           stakingAccount: stakingPubkey,
           userStakingCounterAccount: userStakingCounterPubkey,
           userStakingAccount: nextUserStakingPubkey,
+          walletPositionsAccount: walletPositionsPubkey,
+          eligibilityRulesAccount: eligibilityRulesPubkey,
+          mintBlocklistAccount: mintBlocklistPubkey,
+          tokenMetadataProgram: METADATA_PROGRAM_ID,
           systemProgram: anchor.web3.SystemProgram.programId,
           tokenProgram: TOKEN_PROGRAM_ID,
           rent: anchor.web3.SYSVAR_RENT_PUBKEY,
@@ -1712,329 +3110,2735 @@ describe("nft-staking", () => {
         remainingAccounts,
       }
     );
-  });
 
-  it("Lock the next staking", async () => {
-    await program.rpc.lockStake(
-      stakingBump,
-      userStakingCounterBump,
-      nextUserStakingBump,
-      auryVaultBump,
-      userStakingPeriod,
-      auryDepositAmount,
+    // the next Aurorians join the same position in a second transaction
+    nftVaultBumps = Buffer.from([nftVaultBump[3], nftVaultBump[4]]);
+    remainingAccounts = [
       {
-        accounts: {
-          nftFromAuthority: provider.wallet.publicKey,
-          stakingAccount: stakingPubkey,
-          userStakingCounterAccount: userStakingCounterPubkey,
-          userStakingAccount: nextUserStakingPubkey,
-          auryMint: auryMintPubkey,
-          auryVault: auryVaultPubkey,
-          auryFrom: userAuryTokenAccount,
-          tokenProgram: TOKEN_PROGRAM_ID,
-        },
-      }
-    );
-  });
-
-  it("AddWinner - maximum size is 15", async () => {
-    // Remaining accounts - mint(readonly), userStakingAccount(writable)
-    let remainingAccounts = [
+        pubkey: nftMintPubkey[3],
+        isWritable: false,
+        isSigner: false,
+      },
       {
-        pubkey: rewardMintPubkey[0],
+        pubkey: nftMetadataPubkey[3],
         isWritable: false,
         isSigner: false,
       },
       {
-        pubkey: nextUserStakingPubkey,
+        pubkey: userNFTTokenAccount[3],
         isWritable: true,
         isSigner: false,
       },
       {
-        pubkey: rewardMintPubkey[1],
+        pubkey: nftVaultPubkey[3],
+        isWritable: true,
+        isSigner: false,
+      },
+      {
+        pubkey: nftRegistryPubkey[3],
         isWritable: false,
         isSigner: false,
       },
       {
-        pubkey: nextUserStakingPubkey,
-        isWritable: true,
+        pubkey: nftEditionPubkey[3],
+        isWritable: false,
         isSigner: false,
       },
       {
-        pubkey: rewardMintPubkey[2],
+        pubkey: nftStatsPubkey[3],
         isWritable: false,
         isSigner: false,
       },
       {
-        pubkey: nextUserStakingPubkey,
+        pubkey: nftStakeRecordPubkey[3],
         isWritable: true,
         isSigner: false,
       },
       {
-        pubkey: rewardMintPubkey[3],
+        pubkey: nftMintPubkey[4],
         isWritable: false,
         isSigner: false,
       },
       {
-        pubkey: nextUserStakingPubkey,
+        pubkey: nftMetadataPubkey[4],
+        isWritable: false,
+        isSigner: false,
+      },
+      {
+        pubkey: userNFTTokenAccount[4],
         isWritable: true,
         isSigner: false,
       },
       {
-        pubkey: rewardMintPubkey[4],
+        pubkey: nftVaultPubkey[4],
+        isWritable: true,
+        isSigner: false,
+      },
+      {
+        pubkey: nftRegistryPubkey[4],
         isWritable: false,
         isSigner: false,
       },
       {
-        pubkey: nextUserStakingPubkey,
+        pubkey: nftEditionPubkey[4],
+        isWritable: false,
+        isSigner: false,
+      },
+      {
+        pubkey: nftStatsPubkey[4],
+        isWritable: false,
+        isSigner: false,
+      },
+      {
+        pubkey: nftStakeRecordPubkey[4],
         isWritable: true,
         isSigner: false,
       },
     ];
 
-    // winnerStakingIndex
-    let winnerStakingIndexes = Buffer.from([
-      nextUserStakingIndex,
-      nextUserStakingIndex,
-      nextUserStakingIndex,
-      nextUserStakingIndex,
-      nextUserStakingIndex,
-    ]);
-    // winner
-    let winners = [winner, winner, winner, winner, winner];
-
-    await program.rpc.addWinner(
+    await program.rpc.stake(
+      nftVaultBumps,
       stakingBump,
-      [
-        ...winnerStakingIndexes,
-        ...winnerStakingIndexes,
-        ...winnerStakingIndexes,
-      ],
-      [...winners, ...winners, ...winners],
+      userStakingCounterBump,
+      nextUserStakingBump,
+      eligibilityRulesBump,
+      mintBlocklistBump,
       {
         accounts: {
+          nftFromAuthority: provider.wallet.publicKey,
           stakingAccount: stakingPubkey,
-          admin: provider.wallet.publicKey,
-        },
-        remainingAccounts: [
-          ...remainingAccounts,
-          ...remainingAccounts,
-          ...remainingAccounts,
-        ],
+          userStakingCounterAccount: userStakingCounterPubkey,
+          userStakingAccount: nextUserStakingPubkey,
+          walletPositionsAccount: walletPositionsPubkey,
+          eligibilityRulesAccount: eligibilityRulesPubkey,
+          mintBlocklistAccount: mintBlocklistPubkey,
+          tokenMetadataProgram: METADATA_PROGRAM_ID,
+          systemProgram: anchor.web3.SystemProgram.programId,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        },
+        remainingAccounts,
       }
     );
   });
 
-  it("AddAuryWinner - maximum size is 10", async () => {
-    // Remaining accounts - userStakingAccount(writable)
+  it("Stake items alongside the Aurorians", async () => {
+    let oldBalance = await getTokenBalance(userRewardTokenAccount[0]);
+
+    [itemVaultPubkey, itemVaultBump] =
+      await anchor.web3.PublicKey.findProgramAddress(
+        [provider.wallet.publicKey.toBuffer(), rewardMintPubkey[0].toBuffer()],
+        program.programId
+      );
+
+    // Remaining accounts - mint(readonly), tokenAccount(writable), vault(writable)
     let remainingAccounts = [
       {
-        pubkey: nextUserStakingPubkey,
+        pubkey: rewardMintPubkey[0],
+        isWritable: false,
+        isSigner: false,
+      },
+      {
+        pubkey: userRewardTokenAccount[0],
         isWritable: true,
         isSigner: false,
       },
       {
-        pubkey: nextUserStakingPubkey,
+        pubkey: itemVaultPubkey,
         isWritable: true,
         isSigner: false,
       },
+    ];
+
+    await program.rpc.stakeItems(
+      Buffer.from([itemVaultBump]),
+      [new anchor.BN(2)],
+      stakingBump,
+      userStakingCounterBump,
+      nextUserStakingBump,
       {
-        pubkey: nextUserStakingPubkey,
+        accounts: {
+          nftFromAuthority: provider.wallet.publicKey,
+          stakingAccount: stakingPubkey,
+          userStakingCounterAccount: userStakingCounterPubkey,
+          userStakingAccount: nextUserStakingPubkey,
+          walletPositionsAccount: walletPositionsPubkey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        },
+        remainingAccounts,
+      }
+    );
+
+    assert.equal(
+      await getTokenBalance(userRewardTokenAccount[0]),
+      oldBalance - 2
+    );
+    assert.equal(await getTokenBalance(itemVaultPubkey), 2);
+
+    const userStakingAccount = await program.account.userStakingAccount.fetch(
+      nextUserStakingPubkey
+    );
+    assert.deepEqual(
+      userStakingAccount.stakedItems.map((stakedItem) => ({
+        nftMint: stakedItem.nftMint.toBase58(),
+        amount: stakedItem.amount.toNumber(),
+      })),
+      [{ nftMint: rewardMintPubkey[0].toBase58(), amount: 2 }]
+    );
+  });
+
+  it("Stake items failed with an item vault not at its PDA", async () => {
+    // a token account of the staking account outside the item vault PDA
+    const foreignVaultPubkey = await createTokenAccount(
+      provider,
+      rewardMintPubkey[0],
+      stakingPubkey
+    );
+
+    // Remaining accounts - mint(readonly), tokenAccount(writable), vault(writable)
+    let remainingAccounts = [
+      {
+        pubkey: rewardMintPubkey[0],
+        isWritable: false,
+        isSigner: false,
+      },
+      {
+        pubkey: userRewardTokenAccount[0],
         isWritable: true,
         isSigner: false,
       },
       {
-        pubkey: nextUserStakingPubkey,
+        pubkey: foreignVaultPubkey,
         isWritable: true,
         isSigner: false,
       },
+    ];
+
+    await assert.rejects(
+      async () => {
+        await program.rpc.stakeItems(
+          Buffer.from([itemVaultBump]),
+          [new anchor.BN(1)],
+          stakingBump,
+          userStakingCounterBump,
+          nextUserStakingBump,
+          {
+            accounts: {
+              nftFromAuthority: provider.wallet.publicKey,
+              stakingAccount: stakingPubkey,
+              userStakingCounterAccount: userStakingCounterPubkey,
+              userStakingAccount: nextUserStakingPubkey,
+              walletPositionsAccount: walletPositionsPubkey,
+              systemProgram: anchor.web3.SystemProgram.programId,
+              tokenProgram: TOKEN_PROGRAM_ID,
+              rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+            },
+            remainingAccounts,
+          }
+        );
+      },
       {
-        pubkey: nextUserStakingPubkey,
+        code: 6011,
+        // message: '6011: Derived key invalid',
+      }
+    );
+  });
+
+  it("Update expedition cooldown", async () => {
+    await program.rpc.updateExpeditionCooldown(
+      stakingBump,
+      expeditionCooldown,
+      {
+        accounts: {
+          stakingAccount: stakingPubkey,
+          admin: provider.wallet.publicKey,
+        },
+      }
+    );
+
+    const stakingAccount = await program.account.stakingAccount.fetch(
+      stakingPubkey
+    );
+    assert.equal(
+      stakingAccount.expeditionCooldown.toNumber(),
+      expeditionCooldown.toNumber()
+    );
+  });
+
+  it("Open round", async () => {
+    let enrollmentEnd = new anchor.BN(dayjs().unix() + 3);
+    let departureAt = enrollmentEnd.addn(2);
+    let returnAt = departureAt.add(userStakingPeriod);
+
+    // the return must come after the departure
+    await assert.rejects(
+      async () => {
+        await program.rpc.openRound(
+          stakingBump,
+          roundId,
+          roundBump,
+          enrollmentEnd,
+          enrollmentEnd,
+          enrollmentEnd,
+          {
+            accounts: {
+              stakingAccount: stakingPubkey,
+              roundAccount: roundPubkey,
+              admin: provider.wallet.publicKey,
+              systemProgram: anchor.web3.SystemProgram.programId,
+              rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+            },
+          }
+        );
+      },
+      {
+        code: 6064,
+        // message: '6064: Invalid round',
+      }
+    );
+
+    await program.rpc.openRound(
+      stakingBump,
+      roundId,
+      roundBump,
+      enrollmentEnd,
+      departureAt,
+      returnAt,
+      {
+        accounts: {
+          stakingAccount: stakingPubkey,
+          roundAccount: roundPubkey,
+          admin: provider.wallet.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+          rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        },
+      }
+    );
+
+    const roundAccount = await program.account.roundAccount.fetch(roundPubkey);
+    assert.equal(roundAccount.roundId, roundId);
+    assert.equal(roundAccount.departureAt.toNumber(), departureAt.toNumber());
+    assert.equal(roundAccount.returnAt.toNumber(), returnAt.toNumber());
+    assert.equal(roundAccount.enrolledPositions, 0);
+  });
+
+  it("Lock the next staking", async () => {
+    // Remaining accounts - round(writable), rewardConfig(readonly)
+    let remainingAccounts = [
+      {
+        pubkey: roundPubkey,
         isWritable: true,
         isSigner: false,
       },
+      {
+        pubkey: rewardConfigPubkey,
+        isWritable: false,
+        isSigner: false,
+      },
     ];
 
-    // winnerStakingIndex
-    let winnerStakingIndexes = Buffer.from([
-      nextUserStakingIndex,
-      nextUserStakingIndex,
+    // the round position earns emission for the boost below to raise
+    await program.rpc.updateAuryEmissionRates(
+      stakingBump,
+      [{ minimumStakingPeriod: new anchor.BN(1), rate: new anchor.BN(10) }],
+      {
+        accounts: {
+          stakingAccount: stakingPubkey,
+          admin: provider.wallet.publicKey,
+        },
+      }
+    );
+
+    await program.rpc.lockStake(
+      stakingBump,
+      userStakingCounterBump,
+      nextUserStakingBump,
+      auryVaultBump,
+      userStakingPeriod,
+      auryDepositAmount,
+      {
+        accounts: {
+          nftFromAuthority: provider.wallet.publicKey,
+          stakingAccount: stakingPubkey,
+          userStakingCounterAccount: userStakingCounterPubkey,
+          userStakingAccount: nextUserStakingPubkey,
+          walletPositionsAccount: walletPositionsPubkey,
+          partyRulesAccount: partyRulesPubkey,
+          synergiesAccount: synergiesPubkey,
+          destinationAccount: destinationPubkey,
+          auryMint: auryMintPubkey,
+          auryVault: auryVaultPubkey,
+          auryFrom: userAuryTokenAccount,
+          systemProgram: anchor.web3.SystemProgram.programId,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        },
+        remainingAccounts,
+      }
+    );
+
+    // the staked item is equipped in slot 1
+    const userStakingAccount = await program.account.userStakingAccount.fetch(
+      nextUserStakingPubkey
+    );
+    assert.deepEqual(
+      userStakingAccount.equipment.map((equipment) => ({
+        nftMint: equipment.nftMint.toBase58(),
+        slot: equipment.slot,
+      })),
+      [{ nftMint: rewardMintPubkey[0].toBase58(), slot: 1 }]
+    );
+    assert.equal(userStakingAccount.oddsBoost.toNumber(), 500);
+
+    // the position departs and returns with the round
+    const roundAccount = await program.account.roundAccount.fetch(roundPubkey);
+    assert.equal(userStakingAccount.roundId, roundId);
+    assert.equal(
+      userStakingAccount.stakingAt.toNumber(),
+      roundAccount.departureAt.toNumber()
+    );
+    assert.equal(
+      userStakingAccount.stakingAt.add(userStakingAccount.stakingPeriod).toNumber(),
+      roundAccount.returnAt.toNumber()
+    );
+    assert.equal(roundAccount.enrolledPositions, 1);
+
+    // the cooldown in effect at the lock applies when the party returns
+    assert.equal(
+      userStakingAccount.config.expeditionCooldown.toNumber(),
+      expeditionCooldown.toNumber()
+    );
+    assert.deepEqual(
+      userStakingAccount.config.auryEmissionRates.map((auryEmissionRate) => ({
+        minimumStakingPeriod: auryEmissionRate.minimumStakingPeriod.toNumber(),
+        rate: auryEmissionRate.rate.toNumber(),
+      })),
+      [{ minimumStakingPeriod: 1, rate: 10 }]
+    );
+
+    const walletPositionsAccount =
+      await program.account.walletPositionsAccount.fetch(walletPositionsPubkey);
+    assert.ok(walletPositionsAccount.wallet.equals(provider.wallet.publicKey));
+    assert.equal(walletPositionsAccount.activePositions, 1);
+  });
+
+  it("Use boost raises the emission rate", async () => {
+    const [boostConfigPubkey, boostConfigBump] =
+      await anchor.web3.PublicKey.findProgramAddress(
+        [
+          Buffer.from(anchor.utils.bytes.utf8.encode("reward_config")),
+          rewardMintPubkey[1].toBuffer(),
+        ],
+        program.programId
+      );
+
+    // a 50% reward multiplier per consumed boost
+    await program.rpc.setRewardConfig(
+      stakingBump,
+      boostConfigBump,
+      { slot: 0, auryBoost: 0, oddsBoost: 0 },
+      { lockReduction: new anchor.BN(0), rewardMultiplier: 5000 },
+      {
+        accounts: {
+          stakingAccount: stakingPubkey,
+          nftMint: rewardMintPubkey[1],
+          rewardConfigAccount: boostConfigPubkey,
+          admin: provider.wallet.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+          rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        },
+      }
+    );
+
+    const oldBoostBalance = await getTokenBalance(userRewardTokenAccount[1]);
+    const oldUserStakingAccount =
+      await program.account.userStakingAccount.fetch(nextUserStakingPubkey);
+    const oldStakingAccount = await program.account.stakingAccount.fetch(
+      stakingPubkey
+    );
+    const oldRate = oldUserStakingAccount.auryEmissionRate.toNumber();
+    expect(oldRate).to.be.above(0);
+
+    await program.rpc.useBoost(
+      stakingBump,
       nextUserStakingIndex,
+      nextUserStakingBump,
+      boostConfigBump,
+      new anchor.BN(1),
+      {
+        accounts: {
+          boostFromAuthority: provider.wallet.publicKey,
+          stakingAccount: stakingPubkey,
+          userStakingAccount: nextUserStakingPubkey,
+          boostMint: rewardMintPubkey[1],
+          rewardConfigAccount: boostConfigPubkey,
+          mintBlocklistAccount: mintBlocklistPubkey,
+          boostFrom: userRewardTokenAccount[1],
+          tokenProgram: TOKEN_PROGRAM_ID,
+        },
+      }
+    );
+
+    // the boost is burned
+    assert.equal(
+      await getTokenBalance(userRewardTokenAccount[1]),
+      oldBoostBalance - 1
+    );
+
+    // the raise is reserved from the pool for the time left
+    const userStakingAccount = await program.account.userStakingAccount.fetch(
+      nextUserStakingPubkey
+    );
+    const stakingAccount = await program.account.stakingAccount.fetch(
+      stakingPubkey
+    );
+    const rate = Math.floor((oldRate * 15000) / 10000);
+    assert.equal(userStakingAccount.auryEmissionRate.toNumber(), rate);
+    const auryEmissionReserve =
+      oldStakingAccount.auryEmissionPool.toNumber() -
+      stakingAccount.auryEmissionPool.toNumber();
+    expect(auryEmissionReserve).to.be.above(0);
+    expect(auryEmissionReserve).to.be.at.most(
+      (rate - oldRate) * userStakingAccount.stakingPeriod.toNumber()
+    );
+  });
+
+  it("Claim the aury reward forfeits the emission of a blocked Aurorian", async () => {
+    // reason 1: reported stolen while on the expedition
+    await program.rpc.addBlockedMints(
+      stakingBump,
+      mintBlocklistBump,
+      [{ nftMint: nftMintPubkey[1], reason: 1 }],
+      {
+        accounts: {
+          stakingAccount: stakingPubkey,
+          mintBlocklistAccount: mintBlocklistPubkey,
+          admin: provider.wallet.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+          rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        },
+      }
+    );
+
+    const oldBalance = await getTokenBalance(userAuryTokenAccount);
+    const oldUserStakingAccount =
+      await program.account.userStakingAccount.fetch(nextUserStakingPubkey);
+    const oldStakingAccount = await program.account.stakingAccount.fetch(
+      stakingPubkey
+    );
+    expect(oldUserStakingAccount.auryEmissionRate.toNumber()).to.be.above(0);
+
+    await program.rpc.claimAuryReward(
+      auryVaultBump,
       nextUserStakingIndex,
+      nextUserStakingBump,
+      {
+        accounts: {
+          auryMint: auryMintPubkey,
+          auryVault: auryVaultPubkey,
+          auryTo: userAuryTokenAccount,
+          auryToAuthority: provider.wallet.publicKey,
+          stakingAccount: stakingPubkey,
+          userStakingAccount: nextUserStakingPubkey,
+          mintBlocklistAccount: mintBlocklistPubkey,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        },
+      }
+    );
+
+    // only what was accrued before the block is paid out
+    assert.equal(
+      await getTokenBalance(userAuryTokenAccount),
+      oldBalance + oldUserStakingAccount.claimableAuryAmount.toNumber()
+    );
+
+    // the emission stops and its reserve returns to the pool
+    const userStakingAccount = await program.account.userStakingAccount.fetch(
+      nextUserStakingPubkey
+    );
+    const stakingAccount = await program.account.stakingAccount.fetch(
+      stakingPubkey
+    );
+    assert.equal(userStakingAccount.auryEmissionRate.toNumber(), 0);
+    assert.equal(userStakingAccount.claimableAuryAmount.toNumber(), 0);
+    const returnAt = oldUserStakingAccount.stakingAt
+      .add(oldUserStakingAccount.stakingPeriod)
+      .toNumber();
+    assert.equal(
+      stakingAccount.auryEmissionPool.toNumber() -
+        oldStakingAccount.auryEmissionPool.toNumber(),
+      oldUserStakingAccount.auryEmissionRate.toNumber() *
+        Math.max(
+          returnAt - oldUserStakingAccount.lastAuryAccrualAt.toNumber(),
+          0
+        )
+    );
+
+    await program.rpc.removeBlockedMints(
+      stakingBump,
+      mintBlocklistBump,
+      [nftMintPubkey[1]],
+      {
+        accounts: {
+          stakingAccount: stakingPubkey,
+          mintBlocklistAccount: mintBlocklistPubkey,
+          admin: provider.wallet.publicKey,
+        },
+      }
+    );
+  });
+
+  it("Cancel round", async () => {
+    await program.rpc.cancelRound(stakingBump, roundId, roundBump, {
+      accounts: {
+        stakingAccount: stakingPubkey,
+        roundAccount: roundPubkey,
+        admin: provider.wallet.publicKey,
+      },
+    });
+
+    const roundAccount = await program.account.roundAccount.fetch(roundPubkey);
+    assert.ok(roundAccount.cancelled);
+  });
+
+  it("Unstake items from the cancelled round", async () => {
+    let oldBalance = await getTokenBalance(userRewardTokenAccount[0]);
+    const oldUserStakingAccount =
+      await program.account.userStakingAccount.fetch(nextUserStakingPubkey);
+    const oldStakingAccount = await program.account.stakingAccount.fetch(
+      stakingPubkey
+    );
+
+    // Remaining accounts - round(readonly), mint(readonly), edition(readonly, unused for items), tokenAccount(writable), vault(writable)
+    let remainingAccounts = [
+      {
+        pubkey: roundPubkey,
+        isWritable: false,
+        isSigner: false,
+      },
+      {
+        pubkey: rewardMintPubkey[0],
+        isWritable: false,
+        isSigner: false,
+      },
+      {
+        pubkey: rewardMintPubkey[0],
+        isWritable: false,
+        isSigner: false,
+      },
+      {
+        pubkey: userRewardTokenAccount[0],
+        isWritable: true,
+        isSigner: false,
+      },
+      {
+        pubkey: itemVaultPubkey,
+        isWritable: true,
+        isSigner: false,
+      },
+    ];
+
+    // the position hasn't returned yet
+    await program.rpc.unstake(
+      stakingBump,
       nextUserStakingIndex,
-    ]);
-    // winner
-    let winners = [winner, winner, winner, winner, winner];
-    // auryAmounts
-    let auryAmounts = [
-      userAuryRewardAmount,
-      userAuryRewardAmount,
-      userAuryRewardAmount,
-      userAuryRewardAmount,
-      userAuryRewardAmount,
+      nextUserStakingBump,
+      {
+        accounts: {
+          nftToAuthority: provider.wallet.publicKey,
+          stakingAccount: stakingPubkey,
+          userStakingAccount: nextUserStakingPubkey,
+          walletPositionsAccount: walletPositionsPubkey,
+          destinationAccount: destinationPubkey,
+          tokenMetadataProgram: METADATA_PROGRAM_ID,
+          systemProgram: anchor.web3.SystemProgram.programId,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        },
+        remainingAccounts,
+      }
+    );
+
+    assert.equal(
+      await getTokenBalance(userRewardTokenAccount[0]),
+      oldBalance + 2
+    );
+    await assert.rejects(
+      async () => {
+        await rewardToken[0].getAccountInfo(itemVaultPubkey);
+      },
+      {
+        message: "Failed to find account",
+      }
+    );
+
+    // the deposit and the entry fee are refunded with the next aury claim and the emission stops
+    const oldEntryFee = oldUserStakingAccount.config.entryFee.toNumber();
+    const userStakingAccount = await program.account.userStakingAccount.fetch(
+      nextUserStakingPubkey
+    );
+    assert.equal(userStakingAccount.auryDeposit.toNumber(), 0);
+    assert.equal(userStakingAccount.config.entryFee.toNumber(), 0);
+    assert.equal(userStakingAccount.auryEmissionRate.toNumber(), 0);
+    expect(userStakingAccount.claimableAuryAmount.toNumber()).to.be.at.least(
+      auryDepositAmount.toNumber() + oldEntryFee
+    );
+
+    // the entry fee leaves the emission pool, the emission already stopped with the blocked Aurorian
+    assert.equal(oldUserStakingAccount.auryEmissionRate.toNumber(), 0);
+    const stakingAccount = await program.account.stakingAccount.fetch(
+      stakingPubkey
+    );
+    assert.equal(
+      stakingAccount.auryEmissionPool.toNumber(),
+      oldStakingAccount.auryEmissionPool.toNumber() - oldEntryFee
+    );
+  });
+
+  it("Unstake a position locked before the counters", async () => {
+    // preloaded by Anchor.toml in the 0.1.0 layout
+    const rawData = fs.readFileSync(
+      "tests/keys/legacy-8xKnAsT2KQSxStZzrGw2MSQCYm3JPiGPMJgNjyVmdbhp.json"
+    );
+    const legacyWallet = anchor.web3.Keypair.fromSecretKey(
+      new Uint8Array(JSON.parse(rawData.toString()))
+    );
+    const legacyNftMintPubkey = new PublicKey(
+      "ByERgSRwqsidW2zEXx5fZZ7bKVH8TxEHAHHkLbWMnkLW"
+    );
+    const legacyNftVaultPubkey = new PublicKey(
+      "HueSBnD1UKeY1WZ4pPdY9dCBoeETb43ooedG6AjH1uvb"
+    );
+    const [legacyUserStakingPubkey, legacyUserStakingBump] =
+      await anchor.web3.PublicKey.findProgramAddress(
+        [
+          Buffer.from(anchor.utils.bytes.utf8.encode("0")),
+          legacyWallet.publicKey.toBuffer(),
+        ],
+        program.programId
+      );
+    const [legacyAurorianStatsPubkey] =
+      await anchor.web3.PublicKey.findProgramAddress(
+        [
+          Buffer.from(anchor.utils.bytes.utf8.encode("aurorian_stats")),
+          legacyNftMintPubkey.toBuffer(),
+        ],
+        program.programId
+      );
+    const [legacyStakeRecordPubkey] =
+      await anchor.web3.PublicKey.findProgramAddress(
+        [
+          Buffer.from(anchor.utils.bytes.utf8.encode("stake_record")),
+          legacyNftMintPubkey.toBuffer(),
+        ],
+        program.programId
+      );
+
+    await provider.connection.confirmTransaction(
+      await provider.connection.requestAirdrop(legacyWallet.publicKey, 1e9)
+    );
+    const legacyNftToPubkey = await createTokenAccount(
+      provider,
+      legacyNftMintPubkey,
+      legacyWallet.publicKey
+    );
+
+    await program.rpc.migrateUserStaking(0, legacyUserStakingBump, {
+      accounts: {
+        nftFromAuthority: legacyWallet.publicKey,
+        userStakingAccount: legacyUserStakingPubkey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      },
+      signers: [legacyWallet],
+    });
+
+    let userStakingAccount = await program.account.userStakingAccount.fetch(
+      legacyUserStakingPubkey
+    );
+    assert.equal(userStakingAccount.config.version, 0);
+
+    const oldStakingAccount = await program.account.stakingAccount.fetch(
+      stakingPubkey
+    );
+    const oldDestinationAccount =
+      await program.account.destinationAccount.fetch(destinationPubkey);
+
+    // the position took no seat in the counters, the destination and wallet positions are not read
+    await program.rpc.unstake(stakingBump, 0, legacyUserStakingBump, {
+      accounts: {
+        nftToAuthority: legacyWallet.publicKey,
+        stakingAccount: stakingPubkey,
+        userStakingAccount: legacyUserStakingPubkey,
+        destinationAccount: destinationPubkey,
+        walletPositionsAccount: walletPositionsPubkey,
+        tokenMetadataProgram: METADATA_PROGRAM_ID,
+        systemProgram: anchor.web3.SystemProgram.programId,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      },
+      remainingAccounts: [
+        { pubkey: legacyNftMintPubkey, isWritable: false, isSigner: false },
+        { pubkey: legacyNftMintPubkey, isWritable: false, isSigner: false },
+        { pubkey: legacyNftToPubkey, isWritable: true, isSigner: false },
+        { pubkey: legacyNftVaultPubkey, isWritable: true, isSigner: false },
+        {
+          pubkey: legacyAurorianStatsPubkey,
+          isWritable: true,
+          isSigner: false,
+        },
+        { pubkey: legacyStakeRecordPubkey, isWritable: true, isSigner: false },
+      ],
+      signers: [legacyWallet],
+    });
+
+    assert.equal(await getTokenBalance(legacyNftToPubkey), 1);
+    userStakingAccount = await program.account.userStakingAccount.fetch(
+      legacyUserStakingPubkey
+    );
+    assert.equal(userStakingAccount.nftMintKeys.length, 0);
+
+    const stakingAccount = await program.account.stakingAccount.fetch(
+      stakingPubkey
+    );
+    assert.equal(
+      stakingAccount.activePositions,
+      oldStakingAccount.activePositions
+    );
+    assert.equal(stakingAccount.activeNfts, oldStakingAccount.activeNfts);
+
+    const destinationAccount = await program.account.destinationAccount.fetch(
+      destinationPubkey
+    );
+    assert.equal(
+      destinationAccount.activePositions,
+      oldDestinationAccount.activePositions
+    );
+  });
+
+  it("Stake failed with too many expeditions for the wallet", async () => {
+    await program.rpc.updateCapacity(stakingBump, 1, 10, 1, {
+      accounts: {
+        stakingAccount: stakingPubkey,
+        admin: provider.wallet.publicKey,
+      },
+    });
+
+    const userStakingCounterAccount =
+      await program.account.userStakingCounterAccount.fetch(
+        userStakingCounterPubkey
+      );
+
+    let [fullUserStakingPubkey, fullUserStakingBump] =
+      await anchor.web3.PublicKey.findProgramAddress(
+        [
+          Buffer.from(
+            anchor.utils.bytes.utf8.encode(
+              new anchor.BN(userStakingCounterAccount.counter).toString()
+            )
+          ),
+          provider.wallet.publicKey.toBuffer(),
+        ],
+        program.programId
+      );
+
+    // Remaining accounts - mint(readonly), tokenAccount(writable), vault(writable)
+    let remainingAccounts = [
+      {
+        pubkey: rewardMintPubkey[0],
+        isWritable: false,
+        isSigner: false,
+      },
+      {
+        pubkey: userRewardTokenAccount[0],
+        isWritable: true,
+        isSigner: false,
+      },
+      {
+        pubkey: itemVaultPubkey,
+        isWritable: true,
+        isSigner: false,
+      },
     ];
 
-    await program.rpc.addAuryWinner(
+    await assert.rejects(
+      async () => {
+        await program.rpc.stakeItems(
+          Buffer.from([itemVaultBump]),
+          [new anchor.BN(1)],
+          stakingBump,
+          userStakingCounterBump,
+          fullUserStakingBump,
+          {
+            accounts: {
+              nftFromAuthority: provider.wallet.publicKey,
+              stakingAccount: stakingPubkey,
+              userStakingCounterAccount: userStakingCounterPubkey,
+              userStakingAccount: fullUserStakingPubkey,
+              walletPositionsAccount: walletPositionsPubkey,
+              systemProgram: anchor.web3.SystemProgram.programId,
+              tokenProgram: TOKEN_PROGRAM_ID,
+              rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+            },
+            remainingAccounts,
+          }
+        );
+      },
+      {
+        code: 6063,
+        // message: '6063: Too many expeditions for the wallet',
+      }
+    );
+
+    await program.rpc.updateCapacity(stakingBump, 1, 10, 0, {
+      accounts: {
+        stakingAccount: stakingPubkey,
+        admin: provider.wallet.publicKey,
+      },
+    });
+  });
+
+  it("Use boost failed with not consumable reward", async () => {
+    await assert.rejects(
+      async () => {
+        await program.rpc.useBoost(
+          stakingBump,
+          nextUserStakingIndex,
+          nextUserStakingBump,
+          rewardConfigBump,
+          new anchor.BN(1),
+          {
+            accounts: {
+              boostFromAuthority: provider.wallet.publicKey,
+              stakingAccount: stakingPubkey,
+              userStakingAccount: nextUserStakingPubkey,
+              boostMint: rewardMintPubkey[0],
+              rewardConfigAccount: rewardConfigPubkey,
+              mintBlocklistAccount: mintBlocklistPubkey,
+              boostFrom: userRewardTokenAccount[0],
+              tokenProgram: TOKEN_PROGRAM_ID,
+            },
+          }
+        );
+      },
+      {
+        code: 6046,
+        // message: '6046: Not consumable',
+      }
+    );
+  });
+
+  it("AddWinner - maximum size is 15", async () => {
+    // Remaining accounts - mint(readonly), userStakingAccount(writable)
+    let remainingAccounts = [
+      {
+        pubkey: rewardMintPubkey[0],
+        isWritable: false,
+        isSigner: false,
+      },
+      {
+        pubkey: nextUserStakingPubkey,
+        isWritable: true,
+        isSigner: false,
+      },
+      {
+        pubkey: rewardMintPubkey[1],
+        isWritable: false,
+        isSigner: false,
+      },
+      {
+        pubkey: nextUserStakingPubkey,
+        isWritable: true,
+        isSigner: false,
+      },
+      {
+        pubkey: rewardMintPubkey[2],
+        isWritable: false,
+        isSigner: false,
+      },
+      {
+        pubkey: nextUserStakingPubkey,
+        isWritable: true,
+        isSigner: false,
+      },
+      {
+        pubkey: rewardMintPubkey[3],
+        isWritable: false,
+        isSigner: false,
+      },
+      {
+        pubkey: nextUserStakingPubkey,
+        isWritable: true,
+        isSigner: false,
+      },
+      {
+        pubkey: rewardMintPubkey[4],
+        isWritable: false,
+        isSigner: false,
+      },
+      {
+        pubkey: nextUserStakingPubkey,
+        isWritable: true,
+        isSigner: false,
+      },
+    ];
+
+    // winnerStakingIndex
+    let winnerStakingIndexes = Buffer.from([
+      nextUserStakingIndex,
+      nextUserStakingIndex,
+      nextUserStakingIndex,
+      nextUserStakingIndex,
+      nextUserStakingIndex,
+    ]);
+    // winner
+    let winners = [winner, winner, winner, winner, winner];
+
+    await program.rpc.addWinner(
+      stakingBump,
+      mintBlocklistBump,
+      [
+        ...winnerStakingIndexes,
+        ...winnerStakingIndexes,
+        ...winnerStakingIndexes,
+      ],
+      [...winners, ...winners, ...winners],
+      {
+        accounts: {
+          stakingAccount: stakingPubkey,
+          mintBlocklistAccount: mintBlocklistPubkey,
+          admin: provider.wallet.publicKey,
+        },
+        remainingAccounts: [
+          ...remainingAccounts,
+          ...remainingAccounts,
+          ...remainingAccounts,
+        ],
+      }
+    );
+  });
+
+  it("AddAuryWinner - maximum size is 10", async () => {
+    // Remaining accounts - userStakingAccount(writable)
+    let remainingAccounts = [
+      {
+        pubkey: nextUserStakingPubkey,
+        isWritable: true,
+        isSigner: false,
+      },
+      {
+        pubkey: nextUserStakingPubkey,
+        isWritable: true,
+        isSigner: false,
+      },
+      {
+        pubkey: nextUserStakingPubkey,
+        isWritable: true,
+        isSigner: false,
+      },
+      {
+        pubkey: nextUserStakingPubkey,
+        isWritable: true,
+        isSigner: false,
+      },
+      {
+        pubkey: nextUserStakingPubkey,
+        isWritable: true,
+        isSigner: false,
+      },
+    ];
+
+    // winnerStakingIndex
+    let winnerStakingIndexes = Buffer.from([
+      nextUserStakingIndex,
+      nextUserStakingIndex,
+      nextUserStakingIndex,
+      nextUserStakingIndex,
+      nextUserStakingIndex,
+    ]);
+    // winner
+    let winners = [winner, winner, winner, winner, winner];
+    // auryAmounts
+    let auryAmounts = [
+      userAuryRewardAmount,
+      userAuryRewardAmount,
+      userAuryRewardAmount,
+      userAuryRewardAmount,
+      userAuryRewardAmount,
+    ];
+
+    await program.rpc.addAuryWinner(
+      stakingBump,
+      auryVaultBump,
+      mintBlocklistBump,
+      [...winnerStakingIndexes, ...winnerStakingIndexes],
+      [...winners, ...winners],
+      [...auryAmounts, ...auryAmounts],
+      {
+        accounts: {
+          stakingAccount: stakingPubkey,
+          auryMint: auryMintPubkey,
+          auryVault: auryVaultPubkey,
+          auryFrom: userAuryTokenAccount,
+          mintBlocklistAccount: mintBlocklistPubkey,
+          admin: provider.wallet.publicKey,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        },
+        remainingAccounts: [...remainingAccounts, ...remainingAccounts],
+      }
+    );
+  });
+
+  it("Claim - maximum size is 5", async () => {
+    // Remaining accounts - mint(writable), tokenAccount(writable)
+    let remainingAccounts = [
+      {
+        pubkey: rewardMintPubkey[0],
+        isWritable: true,
+        isSigner: false,
+      },
+      {
+        pubkey: userRewardTokenAccount[0],
+        isWritable: true,
+        isSigner: false,
+      },
+      {
+        pubkey: rewardMintPubkey[1],
+        isWritable: true,
+        isSigner: false,
+      },
+      {
+        pubkey: userRewardTokenAccount[1],
+        isWritable: true,
+        isSigner: false,
+      },
+      {
+        pubkey: rewardMintPubkey[2],
+        isWritable: true,
+        isSigner: false,
+      },
+      {
+        pubkey: userRewardTokenAccount[2],
+        isWritable: true,
+        isSigner: false,
+      },
+      {
+        pubkey: rewardMintPubkey[3],
+        isWritable: true,
+        isSigner: false,
+      },
+      {
+        pubkey: userRewardTokenAccount[3],
+        isWritable: true,
+        isSigner: false,
+      },
+      {
+        pubkey: rewardMintPubkey[4],
+        isWritable: true,
+        isSigner: false,
+      },
+      {
+        pubkey: userRewardTokenAccount[4],
+        isWritable: true,
+        isSigner: false,
+      },
+    ];
+
+    await program.rpc.claim(
+      stakingBump,
+      nextUserStakingIndex,
+      nextUserStakingBump,
+      {
+        accounts: {
+          nftToAuthority: provider.wallet.publicKey,
+          stakingAccount: stakingPubkey,
+          userStakingAccount: nextUserStakingPubkey,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        },
+        remainingAccounts,
+      }
+    );
+  });
+
+  it("Claim the aury reward", async () => {
+    await program.rpc.claimAuryReward(
+      auryVaultBump,
+      nextUserStakingIndex,
+      nextUserStakingBump,
+      {
+        accounts: {
+          auryMint: auryMintPubkey,
+          auryVault: auryVaultPubkey,
+          auryTo: userAuryTokenAccount,
+          auryToAuthority: provider.wallet.publicKey,
+          stakingAccount: stakingPubkey,
+          userStakingAccount: nextUserStakingPubkey,
+          mintBlocklistAccount: mintBlocklistPubkey,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        },
+      }
+    );
+  });
+
+  it("Set recipe", async () => {
+    [recipePubkey, recipeBump] = await anchor.web3.PublicKey.findProgramAddress(
+      [
+        Buffer.from(anchor.utils.bytes.utf8.encode("recipe")),
+        Buffer.from(
+          anchor.utils.bytes.utf8.encode(new anchor.BN(recipeId).toString())
+        ),
+      ],
+      program.programId
+    );
+
+    // 1 reward 2 + 1 reward 3 => 1 reward 5
+    await program.rpc.setRecipe(
+      stakingBump,
+      recipeId,
+      recipeBump,
+      [
+        { nftMint: rewardMintPubkey[2], amount: new anchor.BN(1) },
+        { nftMint: rewardMintPubkey[3], amount: new anchor.BN(1) },
+      ],
+      new anchor.BN(1),
+      new anchor.BN(0),
+      {
+        accounts: {
+          stakingAccount: stakingPubkey,
+          outputMint: rewardMintPubkey[5],
+          recipeAccount: recipePubkey,
+          admin: provider.wallet.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+          rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        },
+      }
+    );
+
+    const recipeAccount = await program.account.recipeAccount.fetch(
+      recipePubkey
+    );
+    assert.equal(recipeAccount.inputs.length, 2);
+    assert.equal(
+      recipeAccount.outputMint.toString(),
+      rewardMintPubkey[5].toString()
+    );
+  });
+
+  it("Craft", async () => {
+    let oldBalances = [
+      await getTokenBalance(userRewardTokenAccount[2]),
+      await getTokenBalance(userRewardTokenAccount[3]),
+      await getTokenBalance(userRewardTokenAccount[5]),
+    ];
+
+    // Remaining accounts - mint(writable), tokenAccount(writable)
+    let remainingAccounts = [
+      {
+        pubkey: rewardMintPubkey[2],
+        isWritable: true,
+        isSigner: false,
+      },
+      {
+        pubkey: userRewardTokenAccount[2],
+        isWritable: true,
+        isSigner: false,
+      },
+      {
+        pubkey: rewardMintPubkey[3],
+        isWritable: true,
+        isSigner: false,
+      },
+      {
+        pubkey: userRewardTokenAccount[3],
+        isWritable: true,
+        isSigner: false,
+      },
+    ];
+
+    await program.rpc.craft(stakingBump, recipeId, recipeBump, {
+      accounts: {
+        crafter: provider.wallet.publicKey,
+        stakingAccount: stakingPubkey,
+        recipeAccount: recipePubkey,
+        outputMint: rewardMintPubkey[5],
+        outputTo: userRewardTokenAccount[5],
+        tokenProgram: TOKEN_PROGRAM_ID,
+      },
+      remainingAccounts,
+    });
+
+    assert.equal(
+      await getTokenBalance(userRewardTokenAccount[2]),
+      oldBalances[0] - 1
+    );
+    assert.equal(
+      await getTokenBalance(userRewardTokenAccount[3]),
+      oldBalances[1] - 1
+    );
+    assert.equal(
+      await getTokenBalance(userRewardTokenAccount[5]),
+      oldBalances[2] + 1
+    );
+
+    // Remove
+    await program.rpc.removeRecipe(stakingBump, recipeId, recipeBump, {
+      accounts: {
+        stakingAccount: stakingPubkey,
+        recipeAccount: recipePubkey,
+        admin: provider.wallet.publicKey,
+      },
+    });
+  });
+
+  it("Unstake - maximum size is 4", async () => {
+    let unstakedAtFloor = dayjs().unix() - 1;
+
+    await sleep(1000);
+
+    // Remaining accounts - mint(readonly), edition(readonly), tokenAccount(writable), vault(writable), aurorianStats(writable), stakeRecord(writable)
+    let remainingAccounts = [
+      {
+        pubkey: nftMintPubkey[0],
+        isWritable: false,
+        isSigner: false,
+      },
+      {
+        pubkey: nftEditionPubkey[0],
+        isWritable: false,
+        isSigner: false,
+      },
+      {
+        pubkey: userNFTTokenAccount[0],
+        isWritable: true,
+        isSigner: false,
+      },
+      {
+        pubkey: nftVaultPubkey[0],
+        isWritable: true,
+        isSigner: false,
+      },
+      {
+        pubkey: nftStatsPubkey[0],
+        isWritable: true,
+        isSigner: false,
+      },
+      {
+        pubkey: nftStakeRecordPubkey[0],
+        isWritable: true,
+        isSigner: false,
+      },
+      {
+        pubkey: nftMintPubkey[1],
+        isWritable: false,
+        isSigner: false,
+      },
+      {
+        pubkey: nftEditionPubkey[1],
+        isWritable: false,
+        isSigner: false,
+      },
+      {
+        pubkey: userNFTTokenAccount[1],
+        isWritable: true,
+        isSigner: false,
+      },
+      {
+        pubkey: nftVaultPubkey[1],
+        isWritable: true,
+        isSigner: false,
+      },
+      {
+        pubkey: nftStatsPubkey[1],
+        isWritable: true,
+        isSigner: false,
+      },
+      {
+        pubkey: nftStakeRecordPubkey[1],
+        isWritable: true,
+        isSigner: false,
+      },
+      {
+        pubkey: nftMintPubkey[2],
+        isWritable: false,
+        isSigner: false,
+      },
+      {
+        pubkey: nftEditionPubkey[2],
+        isWritable: false,
+        isSigner: false,
+      },
+      {
+        pubkey: userNFTTokenAccount[2],
+        isWritable: true,
+        isSigner: false,
+      },
+      {
+        pubkey: nftVaultPubkey[2],
+        isWritable: true,
+        isSigner: false,
+      },
+      {
+        pubkey: nftStatsPubkey[2],
+        isWritable: true,
+        isSigner: false,
+      },
+      {
+        pubkey: nftStakeRecordPubkey[2],
+        isWritable: true,
+        isSigner: false,
+      },
+      {
+        pubkey: nftMintPubkey[3],
+        isWritable: false,
+        isSigner: false,
+      },
+      {
+        pubkey: nftEditionPubkey[3],
+        isWritable: false,
+        isSigner: false,
+      },
+      {
+        pubkey: userNFTTokenAccount[3],
+        isWritable: true,
+        isSigner: false,
+      },
+      {
+        pubkey: nftVaultPubkey[3],
+        isWritable: true,
+        isSigner: false,
+      },
+      {
+        pubkey: nftStatsPubkey[3],
+        isWritable: true,
+        isSigner: false,
+      },
+      {
+        pubkey: nftStakeRecordPubkey[3],
+        isWritable: true,
+        isSigner: false,
+      },
+    ];
+
+    // a fifth Aurorian no longer fits in the transaction, 0.1.0 took up to 5
+    await assert.rejects(
+      async () => {
+        await program.rpc.unstake(
+          stakingBump,
+          nextUserStakingIndex,
+          nextUserStakingBump,
+          {
+            accounts: {
+              nftToAuthority: provider.wallet.publicKey,
+              stakingAccount: stakingPubkey,
+              userStakingAccount: nextUserStakingPubkey,
+              walletPositionsAccount: walletPositionsPubkey,
+              destinationAccount: destinationPubkey,
+              tokenMetadataProgram: METADATA_PROGRAM_ID,
+              systemProgram: anchor.web3.SystemProgram.programId,
+              tokenProgram: TOKEN_PROGRAM_ID,
+            },
+            remainingAccounts: remainingAccounts.concat(
+              [
+                nftMintPubkey[4],
+                nftEditionPubkey[4],
+                userNFTTokenAccount[4],
+                nftVaultPubkey[4],
+                nftStatsPubkey[4],
+                nftStakeRecordPubkey[4],
+              ].map((pubkey) => ({ pubkey, isWritable: true, isSigner: false }))
+            ),
+          }
+        );
+      },
+      isTransactionTooLarge
+    );
+
+    await program.rpc.unstake(
+      stakingBump,
+      nextUserStakingIndex,
+      nextUserStakingBump,
+      {
+        accounts: {
+          nftToAuthority: provider.wallet.publicKey,
+          stakingAccount: stakingPubkey,
+          userStakingAccount: nextUserStakingPubkey,
+          walletPositionsAccount: walletPositionsPubkey,
+          destinationAccount: destinationPubkey,
+          tokenMetadataProgram: METADATA_PROGRAM_ID,
+          systemProgram: anchor.web3.SystemProgram.programId,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        },
+        remainingAccounts,
+      }
+    );
+
+    // the last Aurorian leaves in a second transaction
+    remainingAccounts = [
+      {
+        pubkey: nftMintPubkey[4],
+        isWritable: false,
+        isSigner: false,
+      },
+      {
+        pubkey: nftEditionPubkey[4],
+        isWritable: false,
+        isSigner: false,
+      },
+      {
+        pubkey: userNFTTokenAccount[4],
+        isWritable: true,
+        isSigner: false,
+      },
+      {
+        pubkey: nftVaultPubkey[4],
+        isWritable: true,
+        isSigner: false,
+      },
+      {
+        pubkey: nftStatsPubkey[4],
+        isWritable: true,
+        isSigner: false,
+      },
+      {
+        pubkey: nftStakeRecordPubkey[4],
+        isWritable: true,
+        isSigner: false,
+      },
+    ];
+
+    await program.rpc.unstake(
+      stakingBump,
+      nextUserStakingIndex,
+      nextUserStakingBump,
+      {
+        accounts: {
+          nftToAuthority: provider.wallet.publicKey,
+          stakingAccount: stakingPubkey,
+          userStakingAccount: nextUserStakingPubkey,
+          walletPositionsAccount: walletPositionsPubkey,
+          destinationAccount: destinationPubkey,
+          tokenMetadataProgram: METADATA_PROGRAM_ID,
+          systemProgram: anchor.web3.SystemProgram.programId,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        },
+        remainingAccounts,
+      }
+    );
+
+    // the aurorians rest before their next expedition
+    const aurorianStatsAccount =
+      await program.account.aurorianStatsAccount.fetch(nftStatsPubkey[0]);
+    expect(aurorianStatsAccount.cooldownUntil.toNumber()).to.be.at.least(
+      unstakedAtFloor + expeditionCooldown.toNumber()
+    );
+  });
+
+  it("Stake failed during the expedition cooldown", async () => {
+    const userStakingCounterAccount =
+      await program.account.userStakingCounterAccount.fetch(
+        userStakingCounterPubkey
+      );
+
+    let [cooldownUserStakingPubkey, cooldownUserStakingBump] =
+      await anchor.web3.PublicKey.findProgramAddress(
+        [
+          Buffer.from(
+            anchor.utils.bytes.utf8.encode(
+              new anchor.BN(userStakingCounterAccount.counter).toString()
+            )
+          ),
+          provider.wallet.publicKey.toBuffer(),
+        ],
+        program.programId
+      );
+
+    // Remaining accounts - mint(readonly), metadata(readonly), tokenAccount(writable), vault(writable), aurorianRegistry(readonly), edition(readonly), aurorianStats(readonly), stakeRecord(writable)
+    let remainingAccounts = [
+      {
+        pubkey: nftMintPubkey[0],
+        isWritable: false,
+        isSigner: false,
+      },
+      {
+        pubkey: nftMetadataPubkey[0],
+        isWritable: false,
+        isSigner: false,
+      },
+      {
+        pubkey: userNFTTokenAccount[0],
+        isWritable: true,
+        isSigner: false,
+      },
+      {
+        pubkey: nftVaultPubkey[0],
+        isWritable: true,
+        isSigner: false,
+      },
+      {
+        pubkey: nftRegistryPubkey[0],
+        isWritable: false,
+        isSigner: false,
+      },
+      {
+        pubkey: nftEditionPubkey[0],
+        isWritable: false,
+        isSigner: false,
+      },
+      {
+        pubkey: nftStatsPubkey[0],
+        isWritable: false,
+        isSigner: false,
+      },
+      {
+        pubkey: nftStakeRecordPubkey[0],
+        isWritable: true,
+        isSigner: false,
+      },
+    ];
+
+    await assert.rejects(
+      async () => {
+        await program.rpc.stake(
+          Buffer.from([nftVaultBump[0]]),
+          stakingBump,
+          userStakingCounterBump,
+          cooldownUserStakingBump,
+          eligibilityRulesBump,
+          mintBlocklistBump,
+          {
+            accounts: {
+              nftFromAuthority: provider.wallet.publicKey,
+              stakingAccount: stakingPubkey,
+              userStakingCounterAccount: userStakingCounterPubkey,
+              userStakingAccount: cooldownUserStakingPubkey,
+              walletPositionsAccount: walletPositionsPubkey,
+              eligibilityRulesAccount: eligibilityRulesPubkey,
+              mintBlocklistAccount: mintBlocklistPubkey,
+              tokenMetadataProgram: METADATA_PROGRAM_ID,
+              systemProgram: anchor.web3.SystemProgram.programId,
+              tokenProgram: TOKEN_PROGRAM_ID,
+              rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+            },
+            remainingAccounts,
+          }
+        );
+      },
+      {
+        code: 6052,
+        // message: '6052: Aurorian in cooldown',
+      }
+    );
+  });
+
+  it("Stake an unregistered Aurorian at the lowest rarity", async () => {
+    const unregistered = nftCount - 1;
+    const userStakingCounterAccount =
+      await program.account.userStakingCounterAccount.fetch(
+        userStakingCounterPubkey
+      );
+
+    let [thirdUserStakingPubkey, thirdUserStakingBump] =
+      await anchor.web3.PublicKey.findProgramAddress(
+        [
+          Buffer.from(
+            anchor.utils.bytes.utf8.encode(
+              new anchor.BN(userStakingCounterAccount.counter).toString()
+            )
+          ),
+          provider.wallet.publicKey.toBuffer(),
+        ],
+        program.programId
+      );
+
+    // the registry pda is passed even though the admin never created it
+    assert.equal(
+      await provider.connection.getAccountInfo(nftRegistryPubkey[unregistered]),
+      null
+    );
+
+    // Remaining accounts - mint(readonly), metadata(readonly), tokenAccount(writable), vault(writable), aurorianRegistry(readonly), edition(readonly), aurorianStats(readonly), stakeRecord(writable)
+    let remainingAccounts = [
+      {
+        pubkey: nftMintPubkey[unregistered],
+        isWritable: false,
+        isSigner: false,
+      },
+      {
+        pubkey: nftMetadataPubkey[unregistered],
+        isWritable: false,
+        isSigner: false,
+      },
+      {
+        pubkey: userNFTTokenAccount[unregistered],
+        isWritable: true,
+        isSigner: false,
+      },
+      {
+        pubkey: nftVaultPubkey[unregistered],
+        isWritable: true,
+        isSigner: false,
+      },
+      {
+        pubkey: nftRegistryPubkey[unregistered],
+        isWritable: false,
+        isSigner: false,
+      },
+      {
+        pubkey: nftEditionPubkey[unregistered],
+        isWritable: false,
+        isSigner: false,
+      },
+      {
+        pubkey: nftStatsPubkey[unregistered],
+        isWritable: false,
+        isSigner: false,
+      },
+      {
+        pubkey: nftStakeRecordPubkey[unregistered],
+        isWritable: true,
+        isSigner: false,
+      },
+    ];
+
+    await program.rpc.stake(
+      Buffer.from([nftVaultBump[unregistered]]),
+      stakingBump,
+      userStakingCounterBump,
+      thirdUserStakingBump,
+      eligibilityRulesBump,
+      mintBlocklistBump,
+      {
+        accounts: {
+          nftFromAuthority: provider.wallet.publicKey,
+          stakingAccount: stakingPubkey,
+          userStakingCounterAccount: userStakingCounterPubkey,
+          userStakingAccount: thirdUserStakingPubkey,
+          walletPositionsAccount: walletPositionsPubkey,
+          eligibilityRulesAccount: eligibilityRulesPubkey,
+          mintBlocklistAccount: mintBlocklistPubkey,
+          tokenMetadataProgram: METADATA_PROGRAM_ID,
+          systemProgram: anchor.web3.SystemProgram.programId,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        },
+        remainingAccounts,
+      }
+    );
+
+    assert.equal(await getTokenBalance(nftVaultPubkey[unregistered]), 1);
+
+    const thirdUserStakingAccount =
+      await program.account.userStakingAccount.fetch(thirdUserStakingPubkey);
+    assert.equal(
+      thirdUserStakingAccount.nftMintKeys.toString(),
+      [nftMintPubkey[unregistered]].toString()
+    );
+    assert.equal(thirdUserStakingAccount.stakedNfts[0].rarity, 0);
+    assert.equal(thirdUserStakingAccount.stakedNfts[0].traits, 0);
+  });
+
+  it("Prepare Token-2022 NFT that will be staked", async () => {
+    // the mint key matches the preloaded metadata
+    const rawData = fs.readFileSync(
+      "tests/keys/token-2022-EYMes71kj28VLWyfTeSS24QeJ1Z3twos639WJEzW45Gp.json"
+    );
+    const mint = anchor.web3.Keypair.fromSecretKey(
+      new Uint8Array(JSON.parse(rawData.toString()))
+    );
+
+    const token = await createTokenMint(
+      provider,
+      mint,
+      provider.wallet.publicKey,
+      null,
+      0,
+      TOKEN_2022_PROGRAM_ID
+    );
+    nftMintPubkey.push(mint.publicKey);
+    nftToken.push(token);
+
+    const tokenAccount = await token.createAccount(provider.wallet.publicKey);
+    await mintToAccount(
+      provider,
+      mint.publicKey,
+      tokenAccount,
+      1,
+      TOKEN_2022_PROGRAM_ID
+    );
+    userNFTTokenAccount.push(tokenAccount);
+
+    let [pubkey, bump] = await anchor.web3.PublicKey.findProgramAddress(
+      [
+        Buffer.from(anchor.utils.bytes.utf8.encode("metadata")),
+        METADATA_PROGRAM_ID.toBuffer(),
+        mint.publicKey.toBuffer(),
+      ],
+      METADATA_PROGRAM_ID
+    );
+    nftMetadataPubkey.push(pubkey);
+
+    [pubkey, bump] = await anchor.web3.PublicKey.findProgramAddress(
+      [provider.wallet.publicKey.toBuffer(), mint.publicKey.toBuffer()],
+      program.programId
+    );
+    nftVaultPubkey.push(pubkey);
+    nftVaultBump.push(bump);
+
+    [pubkey, bump] = await anchor.web3.PublicKey.findProgramAddress(
+      [
+        Buffer.from(anchor.utils.bytes.utf8.encode("aurorian_registry")),
+        mint.publicKey.toBuffer(),
+      ],
+      program.programId
+    );
+    nftRegistryPubkey.push(pubkey);
+    nftRegistryBump.push(bump);
+
+    [pubkey, bump] = await anchor.web3.PublicKey.findProgramAddress(
+      [
+        Buffer.from(anchor.utils.bytes.utf8.encode("aurorian_stats")),
+        mint.publicKey.toBuffer(),
+      ],
+      program.programId
+    );
+    nftStatsPubkey.push(pubkey);
+
+    [pubkey, bump] = await anchor.web3.PublicKey.findProgramAddress(
+      [
+        Buffer.from(anchor.utils.bytes.utf8.encode("stake_record")),
+        mint.publicKey.toBuffer(),
+      ],
+      program.programId
+    );
+    nftStakeRecordPubkey.push(pubkey);
+
+    [pubkey, bump] = await anchor.web3.PublicKey.findProgramAddress(
+      [
+        Buffer.from(anchor.utils.bytes.utf8.encode("metadata")),
+        METADATA_PROGRAM_ID.toBuffer(),
+        mint.publicKey.toBuffer(),
+        Buffer.from(anchor.utils.bytes.utf8.encode("edition")),
+      ],
+      METADATA_PROGRAM_ID
+    );
+    nftEditionPubkey.push(pubkey);
+
+    // the preloaded metadata has a verified collection
+    await program.rpc.addAuthorizedCollections(
+      stakingBump,
+      [token2022NftCollection],
+      {
+        accounts: {
+          stakingAccount: stakingPubkey,
+          admin: provider.wallet.publicKey,
+        },
+      }
+    );
+  });
+
+  it("Stake failed non-custodially with a Token-2022 NFT", async () => {
+    const userStakingCounterAccount =
+      await program.account.userStakingCounterAccount.fetch(
+        userStakingCounterPubkey
+      );
+
+    let [thirdUserStakingPubkey, thirdUserStakingBump] =
+      await anchor.web3.PublicKey.findProgramAddress(
+        [
+          Buffer.from(
+            anchor.utils.bytes.utf8.encode(
+              new anchor.BN(userStakingCounterAccount.counter).toString()
+            )
+          ),
+          provider.wallet.publicKey.toBuffer(),
+        ],
+        program.programId
+      );
+
+    // Remaining accounts - mint(readonly), metadata(readonly), tokenAccount(writable), vault(writable), aurorianRegistry(readonly), edition(readonly), aurorianStats(readonly), stakeRecord(writable)
+    let remainingAccounts = [
+      {
+        pubkey: nftMintPubkey[token2022Nft],
+        isWritable: false,
+        isSigner: false,
+      },
+      {
+        pubkey: nftMetadataPubkey[token2022Nft],
+        isWritable: false,
+        isSigner: false,
+      },
+      {
+        pubkey: userNFTTokenAccount[token2022Nft],
+        isWritable: true,
+        isSigner: false,
+      },
+      {
+        pubkey: userNFTTokenAccount[token2022Nft],
+        isWritable: true,
+        isSigner: false,
+      },
+      {
+        pubkey: nftRegistryPubkey[token2022Nft],
+        isWritable: false,
+        isSigner: false,
+      },
+      {
+        pubkey: nftEditionPubkey[token2022Nft],
+        isWritable: false,
+        isSigner: false,
+      },
+      {
+        pubkey: nftStatsPubkey[token2022Nft],
+        isWritable: false,
+        isSigner: false,
+      },
+      {
+        pubkey: nftStakeRecordPubkey[token2022Nft],
+        isWritable: true,
+        isSigner: false,
+      },
+    ];
+
+    // metaplex can't freeze a Token-2022 account
+    await assert.rejects(
+      async () => {
+        await program.rpc.stake(
+          Buffer.from([nftVaultBump[token2022Nft]]),
+          stakingBump,
+          userStakingCounterBump,
+          thirdUserStakingBump,
+          eligibilityRulesBump,
+          mintBlocklistBump,
+          {
+            accounts: {
+              nftFromAuthority: provider.wallet.publicKey,
+              stakingAccount: stakingPubkey,
+              userStakingCounterAccount: userStakingCounterPubkey,
+              userStakingAccount: thirdUserStakingPubkey,
+              walletPositionsAccount: walletPositionsPubkey,
+              eligibilityRulesAccount: eligibilityRulesPubkey,
+              mintBlocklistAccount: mintBlocklistPubkey,
+              tokenMetadataProgram: METADATA_PROGRAM_ID,
+              systemProgram: anchor.web3.SystemProgram.programId,
+              tokenProgram: TOKEN_2022_PROGRAM_ID,
+              rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+            },
+            remainingAccounts,
+          }
+        );
+      },
+      {
+        code: 6068,
+        // message: '6068: Non-custodial staking is only supported for SPL Token mints',
+      }
+    );
+  });
+
+  it("Stake a Token-2022 NFT", async () => {
+    const userStakingCounterAccount =
+      await program.account.userStakingCounterAccount.fetch(
+        userStakingCounterPubkey
+      );
+
+    let [thirdUserStakingPubkey, thirdUserStakingBump] =
+      await anchor.web3.PublicKey.findProgramAddress(
+        [
+          Buffer.from(
+            anchor.utils.bytes.utf8.encode(
+              new anchor.BN(userStakingCounterAccount.counter).toString()
+            )
+          ),
+          provider.wallet.publicKey.toBuffer(),
+        ],
+        program.programId
+      );
+
+    // Remaining accounts - mint(readonly), metadata(readonly), tokenAccount(writable), vault(writable), aurorianRegistry(readonly), edition(readonly), aurorianStats(readonly), stakeRecord(writable)
+    let remainingAccounts = [
+      {
+        pubkey: nftMintPubkey[token2022Nft],
+        isWritable: false,
+        isSigner: false,
+      },
+      {
+        pubkey: nftMetadataPubkey[token2022Nft],
+        isWritable: false,
+        isSigner: false,
+      },
+      {
+        pubkey: userNFTTokenAccount[token2022Nft],
+        isWritable: true,
+        isSigner: false,
+      },
+      {
+        pubkey: nftVaultPubkey[token2022Nft],
+        isWritable: true,
+        isSigner: false,
+      },
+      {
+        pubkey: nftRegistryPubkey[token2022Nft],
+        isWritable: false,
+        isSigner: false,
+      },
+      {
+        pubkey: nftEditionPubkey[token2022Nft],
+        isWritable: false,
+        isSigner: false,
+      },
+      {
+        pubkey: nftStatsPubkey[token2022Nft],
+        isWritable: false,
+        isSigner: false,
+      },
+      {
+        pubkey: nftStakeRecordPubkey[token2022Nft],
+        isWritable: true,
+        isSigner: false,
+      },
+    ];
+
+    await program.rpc.stake(
+      Buffer.from([nftVaultBump[token2022Nft]]),
+      stakingBump,
+      userStakingCounterBump,
+      thirdUserStakingBump,
+      eligibilityRulesBump,
+      mintBlocklistBump,
+      {
+        accounts: {
+          nftFromAuthority: provider.wallet.publicKey,
+          stakingAccount: stakingPubkey,
+          userStakingCounterAccount: userStakingCounterPubkey,
+          userStakingAccount: thirdUserStakingPubkey,
+          walletPositionsAccount: walletPositionsPubkey,
+          eligibilityRulesAccount: eligibilityRulesPubkey,
+          mintBlocklistAccount: mintBlocklistPubkey,
+          tokenMetadataProgram: METADATA_PROGRAM_ID,
+          systemProgram: anchor.web3.SystemProgram.programId,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+          rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        },
+        remainingAccounts,
+      }
+    );
+
+    assert.equal(await getTokenBalance(userNFTTokenAccount[token2022Nft]), 0);
+    assert.equal(await getTokenBalance(nftVaultPubkey[token2022Nft]), 1);
+
+    // it leads the party of the unregistered Aurorian
+    const thirdUserStakingAccount =
+      await program.account.userStakingAccount.fetch(thirdUserStakingPubkey);
+    assert.equal(
+      thirdUserStakingAccount.nftMintKeys.toString(),
+      [nftMintPubkey[nftCount - 1], nftMintPubkey[token2022Nft]].toString()
+    );
+    assert.equal(
+      thirdUserStakingAccount.stakedNfts[1].name,
+      "Helios Token-2022"
+    );
+  });
+
+  it("Prepare master edition NFT that will be staked in place", async () => {
+    const data = nft_data(
+      provider.wallet.publicKey,
+      authorizedNameStarts[1] + ": #" + (nonCustodialNft + 1).toString()
+    );
+    const lamports = await Token.getMinBalanceRentForExemptMint(
+      provider.connection
+    );
+    const [mint, metadataPDA, tx] = await createMint(
+      provider.wallet.publicKey,
+      provider.wallet.publicKey,
+      lamports,
+      data,
+      nft_json_url
+    );
+    await provider.send(tx, [mint]);
+
+    nftMintPubkey.push(mint.publicKey);
+    nftMetadataPubkey.push(metadataPDA);
+    nftToken.push(
+      new Token(provider.connection, mint.publicKey, TOKEN_PROGRAM_ID, null)
+    );
+    const tokenAccount = await createTokenAccount(
+      provider,
+      mint.publicKey,
+      provider.wallet.publicKey
+    );
+    await mintToAccount(provider, mint.publicKey, tokenAccount, 1);
+    userNFTTokenAccount.push(tokenAccount);
+
+    // the master edition freezes and thaws the token account
+    nftEditionPubkey.push(await createMasterEdition(provider, mint.publicKey));
+
+    // a vault pda is never created for it
+    let [pubkey, bump] = await anchor.web3.PublicKey.findProgramAddress(
+      [provider.wallet.publicKey.toBuffer(), mint.publicKey.toBuffer()],
+      program.programId
+    );
+    nftVaultPubkey.push(pubkey);
+    nftVaultBump.push(bump);
+
+    [pubkey, bump] = await anchor.web3.PublicKey.findProgramAddress(
+      [
+        Buffer.from(anchor.utils.bytes.utf8.encode("aurorian_registry")),
+        mint.publicKey.toBuffer(),
+      ],
+      program.programId
+    );
+    nftRegistryPubkey.push(pubkey);
+    nftRegistryBump.push(bump);
+
+    [pubkey, bump] = await anchor.web3.PublicKey.findProgramAddress(
+      [
+        Buffer.from(anchor.utils.bytes.utf8.encode("aurorian_stats")),
+        mint.publicKey.toBuffer(),
+      ],
+      program.programId
+    );
+    nftStatsPubkey.push(pubkey);
+
+    [pubkey, bump] = await anchor.web3.PublicKey.findProgramAddress(
+      [
+        Buffer.from(anchor.utils.bytes.utf8.encode("stake_record")),
+        mint.publicKey.toBuffer(),
+      ],
+      program.programId
+    );
+    nftStakeRecordPubkey.push(pubkey);
+  });
+
+  it("Stake an NFT non-custodially", async () => {
+    const userStakingCounterAccount =
+      await program.account.userStakingCounterAccount.fetch(
+        userStakingCounterPubkey
+      );
+
+    let [thirdUserStakingPubkey, thirdUserStakingBump] =
+      await anchor.web3.PublicKey.findProgramAddress(
+        [
+          Buffer.from(
+            anchor.utils.bytes.utf8.encode(
+              new anchor.BN(userStakingCounterAccount.counter).toString()
+            )
+          ),
+          provider.wallet.publicKey.toBuffer(),
+        ],
+        program.programId
+      );
+
+    // Remaining accounts - mint(readonly), metadata(readonly), tokenAccount(writable), vault(writable), aurorianRegistry(readonly), edition(readonly), aurorianStats(readonly), stakeRecord(writable)
+    let remainingAccounts = [
+      {
+        pubkey: nftMintPubkey[nonCustodialNft],
+        isWritable: false,
+        isSigner: false,
+      },
+      {
+        pubkey: nftMetadataPubkey[nonCustodialNft],
+        isWritable: false,
+        isSigner: false,
+      },
+      {
+        pubkey: userNFTTokenAccount[nonCustodialNft],
+        isWritable: true,
+        isSigner: false,
+      },
+      // the token account is passed as the vault to keep the nft in place
+      {
+        pubkey: userNFTTokenAccount[nonCustodialNft],
+        isWritable: true,
+        isSigner: false,
+      },
+      {
+        pubkey: nftRegistryPubkey[nonCustodialNft],
+        isWritable: false,
+        isSigner: false,
+      },
+      {
+        pubkey: nftEditionPubkey[nonCustodialNft],
+        isWritable: false,
+        isSigner: false,
+      },
+      {
+        pubkey: nftStatsPubkey[nonCustodialNft],
+        isWritable: false,
+        isSigner: false,
+      },
+      {
+        pubkey: nftStakeRecordPubkey[nonCustodialNft],
+        isWritable: true,
+        isSigner: false,
+      },
+    ];
+
+    await program.rpc.stake(
+      Buffer.from([nftVaultBump[nonCustodialNft]]),
+      stakingBump,
+      userStakingCounterBump,
+      thirdUserStakingBump,
+      eligibilityRulesBump,
+      mintBlocklistBump,
+      {
+        accounts: {
+          nftFromAuthority: provider.wallet.publicKey,
+          stakingAccount: stakingPubkey,
+          userStakingCounterAccount: userStakingCounterPubkey,
+          userStakingAccount: thirdUserStakingPubkey,
+          walletPositionsAccount: walletPositionsPubkey,
+          eligibilityRulesAccount: eligibilityRulesPubkey,
+          mintBlocklistAccount: mintBlocklistPubkey,
+          tokenMetadataProgram: METADATA_PROGRAM_ID,
+          systemProgram: anchor.web3.SystemProgram.programId,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        },
+        remainingAccounts,
+      }
+    );
+
+    // the nft stays in the wallet, frozen and delegated to the staking account
+    const tokenAccount = await nftToken[nonCustodialNft].getAccountInfo(
+      userNFTTokenAccount[nonCustodialNft]
+    );
+    assert.equal(tokenAccount.amount.toNumber(), 1);
+    assert.ok(tokenAccount.isFrozen);
+    assert.ok(tokenAccount.delegate.equals(stakingPubkey));
+    assert.equal(tokenAccount.delegatedAmount.toNumber(), 1);
+    assert.equal(
+      await provider.connection.getAccountInfo(nftVaultPubkey[nonCustodialNft]),
+      null
+    );
+
+    const thirdUserStakingAccount =
+      await program.account.userStakingAccount.fetch(thirdUserStakingPubkey);
+    assert.ok(thirdUserStakingAccount.stakedNfts[2].nonCustodial);
+    const stakeRecordAccount = await program.account.stakeRecordAccount.fetch(
+      nftStakeRecordPubkey[nonCustodialNft]
+    );
+    assert.ok(
+      stakeRecordAccount.vault.equals(userNFTTokenAccount[nonCustodialNft])
+    );
+  });
+
+  it("Lock stake failed with a cancelled round", async () => {
+    const userStakingCounterAccount =
+      await program.account.userStakingCounterAccount.fetch(
+        userStakingCounterPubkey
+      );
+
+    let [thirdUserStakingPubkey, thirdUserStakingBump] =
+      await anchor.web3.PublicKey.findProgramAddress(
+        [
+          Buffer.from(
+            anchor.utils.bytes.utf8.encode(
+              new anchor.BN(userStakingCounterAccount.counter).toString()
+            )
+          ),
+          provider.wallet.publicKey.toBuffer(),
+        ],
+        program.programId
+      );
+
+    await assert.rejects(
+      async () => {
+        await program.rpc.lockStake(
+          stakingBump,
+          userStakingCounterBump,
+          thirdUserStakingBump,
+          auryVaultBump,
+          userStakingPeriod,
+          new anchor.BN(0),
+          {
+            accounts: {
+              nftFromAuthority: provider.wallet.publicKey,
+              stakingAccount: stakingPubkey,
+              userStakingCounterAccount: userStakingCounterPubkey,
+              userStakingAccount: thirdUserStakingPubkey,
+              walletPositionsAccount: walletPositionsPubkey,
+              partyRulesAccount: partyRulesPubkey,
+              synergiesAccount: synergiesPubkey,
+              destinationAccount: destinationPubkey,
+              auryMint: auryMintPubkey,
+              auryVault: auryVaultPubkey,
+              auryFrom: userAuryTokenAccount,
+              systemProgram: anchor.web3.SystemProgram.programId,
+              tokenProgram: TOKEN_PROGRAM_ID,
+            },
+            remainingAccounts: [
+              {
+                pubkey: roundPubkey,
+                isWritable: true,
+                isSigner: false,
+              },
+            ],
+          }
+        );
+      },
+      {
+        code: 6065,
+        // message: "6065: Round isn't open for enrollment",
+      }
+    );
+  });
+
+  it("Lock stake failed after the round enrollment closed", async () => {
+    const userStakingCounterAccount =
+      await program.account.userStakingCounterAccount.fetch(
+        userStakingCounterPubkey
+      );
+
+    let [thirdUserStakingPubkey, thirdUserStakingBump] =
+      await anchor.web3.PublicKey.findProgramAddress(
+        [
+          Buffer.from(
+            anchor.utils.bytes.utf8.encode(
+              new anchor.BN(userStakingCounterAccount.counter).toString()
+            )
+          ),
+          provider.wallet.publicKey.toBuffer(),
+        ],
+        program.programId
+      );
+
+    let closedRoundId = roundId + 1;
+    let [closedRoundPubkey, closedRoundBump] =
+      await anchor.web3.PublicKey.findProgramAddress(
+        [
+          Buffer.from(anchor.utils.bytes.utf8.encode("round")),
+          Buffer.from(
+            anchor.utils.bytes.utf8.encode(
+              new anchor.BN(closedRoundId).toString()
+            )
+          ),
+        ],
+        program.programId
+      );
+
+    let enrollmentEnd = new anchor.BN(dayjs().unix() + 1);
+    let returnAt = enrollmentEnd.add(userStakingPeriod);
+    await program.rpc.openRound(
+      stakingBump,
+      closedRoundId,
+      closedRoundBump,
+      enrollmentEnd,
+      enrollmentEnd,
+      returnAt,
+      {
+        accounts: {
+          stakingAccount: stakingPubkey,
+          roundAccount: closedRoundPubkey,
+          admin: provider.wallet.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+          rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        },
+      }
+    );
+
+    await sleep(
+      Math.max(enrollmentEnd.toNumber() + 1 - dayjs().unix(), 0) * 1000
+    );
+
+    await assert.rejects(
+      async () => {
+        await program.rpc.lockStake(
+          stakingBump,
+          userStakingCounterBump,
+          thirdUserStakingBump,
+          auryVaultBump,
+          userStakingPeriod,
+          new anchor.BN(0),
+          {
+            accounts: {
+              nftFromAuthority: provider.wallet.publicKey,
+              stakingAccount: stakingPubkey,
+              userStakingCounterAccount: userStakingCounterPubkey,
+              userStakingAccount: thirdUserStakingPubkey,
+              walletPositionsAccount: walletPositionsPubkey,
+              partyRulesAccount: partyRulesPubkey,
+              synergiesAccount: synergiesPubkey,
+              destinationAccount: destinationPubkey,
+              auryMint: auryMintPubkey,
+              auryVault: auryVaultPubkey,
+              auryFrom: userAuryTokenAccount,
+              systemProgram: anchor.web3.SystemProgram.programId,
+              tokenProgram: TOKEN_PROGRAM_ID,
+            },
+            remainingAccounts: [
+              {
+                pubkey: closedRoundPubkey,
+                isWritable: true,
+                isSigner: false,
+              },
+            ],
+          }
+        );
+      },
+      {
+        code: 6065,
+        // message: "6065: Round isn't open for enrollment",
+      }
+    );
+  });
+
+  it("Extend expedition moves the aury emission to the tier of the extended period", async () => {
+    const userStakingCounterAccount =
+      await program.account.userStakingCounterAccount.fetch(
+        userStakingCounterPubkey
+      );
+    const thirdUserStakingIndex = userStakingCounterAccount.counter;
+
+    let [thirdUserStakingPubkey, thirdUserStakingBump] =
+      await anchor.web3.PublicKey.findProgramAddress(
+        [
+          Buffer.from(
+            anchor.utils.bytes.utf8.encode(
+              new anchor.BN(thirdUserStakingIndex).toString()
+            )
+          ),
+          provider.wallet.publicKey.toBuffer(),
+        ],
+        program.programId
+      );
+
+    // the maximum staking period reaches the second tier
+    await program.rpc.updateAuryEmissionRates(
+      stakingBump,
+      [
+        { minimumStakingPeriod: new anchor.BN(1), rate: new anchor.BN(10) },
+        { minimumStakingPeriod: maximumStakingPeriod, rate: new anchor.BN(20) },
+      ],
+      {
+        accounts: {
+          stakingAccount: stakingPubkey,
+          admin: provider.wallet.publicKey,
+        },
+      }
+    );
+
+    await program.rpc.lockStake(
       stakingBump,
+      userStakingCounterBump,
+      thirdUserStakingBump,
       auryVaultBump,
-      [...winnerStakingIndexes, ...winnerStakingIndexes],
-      [...winners, ...winners],
-      [...auryAmounts, ...auryAmounts],
+      userStakingPeriod,
+      new anchor.BN(0),
       {
         accounts: {
+          nftFromAuthority: provider.wallet.publicKey,
           stakingAccount: stakingPubkey,
+          userStakingCounterAccount: userStakingCounterPubkey,
+          userStakingAccount: thirdUserStakingPubkey,
+          walletPositionsAccount: walletPositionsPubkey,
+          partyRulesAccount: partyRulesPubkey,
+          synergiesAccount: synergiesPubkey,
+          destinationAccount: destinationPubkey,
           auryMint: auryMintPubkey,
           auryVault: auryVaultPubkey,
           auryFrom: userAuryTokenAccount,
-          admin: provider.wallet.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
           tokenProgram: TOKEN_PROGRAM_ID,
         },
-        remainingAccounts: [...remainingAccounts, ...remainingAccounts],
       }
     );
-  });
 
-  it("Claim - maximum size is 5", async () => {
-    // Remaining accounts - mint(writable), tokenAccount(writable)
-    let remainingAccounts = [
+    const oldUserStakingAccount =
+      await program.account.userStakingAccount.fetch(thirdUserStakingPubkey);
+    const oldStakingAccount = await program.account.stakingAccount.fetch(
+      stakingPubkey
+    );
+    expect(oldUserStakingAccount.auryEmissionRate.toNumber()).to.be.above(0);
+
+    await program.rpc.extendExpedition(
+      stakingBump,
+      thirdUserStakingIndex,
+      thirdUserStakingBump,
+      auryVaultBump,
+      maximumStakingPeriod.sub(userStakingPeriod),
+      new anchor.BN(0),
       {
-        pubkey: rewardMintPubkey[0],
-        isWritable: true,
-        isSigner: false,
+        accounts: {
+          nftFromAuthority: provider.wallet.publicKey,
+          stakingAccount: stakingPubkey,
+          userStakingAccount: thirdUserStakingPubkey,
+          auryMint: auryMintPubkey,
+          auryVault: auryVaultPubkey,
+          auryFrom: userAuryTokenAccount,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        },
+      }
+    );
+
+    // the rate doubles with the tier and the pool reserves the rest of the expedition at it
+    const userStakingAccount = await program.account.userStakingAccount.fetch(
+      thirdUserStakingPubkey
+    );
+    const stakingAccount = await program.account.stakingAccount.fetch(
+      stakingPubkey
+    );
+    assert.equal(
+      userStakingAccount.stakingPeriod.toNumber(),
+      maximumStakingPeriod.toNumber()
+    );
+    assert.equal(
+      userStakingAccount.auryEmissionRate.toNumber(),
+      oldUserStakingAccount.auryEmissionRate.toNumber() * 2
+    );
+    expect(stakingAccount.auryEmissionPool.toNumber()).to.be.below(
+      oldStakingAccount.auryEmissionPool.toNumber()
+    );
+
+    await program.rpc.updateAuryEmissionRates(stakingBump, [], {
+      accounts: {
+        stakingAccount: stakingPubkey,
+        admin: provider.wallet.publicKey,
       },
-      {
-        pubkey: userRewardTokenAccount[0],
-        isWritable: true,
-        isSigner: false,
+    });
+  });
+
+  it("Use boost failed after the expedition returned", async () => {
+    const userStakingCounterAccount =
+      await program.account.userStakingCounterAccount.fetch(
+        userStakingCounterPubkey
+      );
+    // the position locked and extended above
+    const thirdUserStakingIndex = userStakingCounterAccount.counter - 1;
+
+    let [thirdUserStakingPubkey, thirdUserStakingBump] =
+      await anchor.web3.PublicKey.findProgramAddress(
+        [
+          Buffer.from(
+            anchor.utils.bytes.utf8.encode(
+              new anchor.BN(thirdUserStakingIndex).toString()
+            )
+          ),
+          provider.wallet.publicKey.toBuffer(),
+        ],
+        program.programId
+      );
+    const [boostConfigPubkey, boostConfigBump] =
+      await anchor.web3.PublicKey.findProgramAddress(
+        [
+          Buffer.from(anchor.utils.bytes.utf8.encode("reward_config")),
+          rewardMintPubkey[1].toBuffer(),
+        ],
+        program.programId
+      );
+
+    const userStakingAccount = await program.account.userStakingAccount.fetch(
+      thirdUserStakingPubkey
+    );
+    const returnAt = userStakingAccount.stakingAt
+      .add(userStakingAccount.stakingPeriod)
+      .toNumber();
+    await sleep(Math.max(returnAt + 1 - dayjs().unix(), 0) * 1000);
+
+    await assert.rejects(
+      async () => {
+        await program.rpc.useBoost(
+          stakingBump,
+          thirdUserStakingIndex,
+          thirdUserStakingBump,
+          boostConfigBump,
+          new anchor.BN(1),
+          {
+            accounts: {
+              boostFromAuthority: provider.wallet.publicKey,
+              stakingAccount: stakingPubkey,
+              userStakingAccount: thirdUserStakingPubkey,
+              boostMint: rewardMintPubkey[1],
+              rewardConfigAccount: boostConfigPubkey,
+              mintBlocklistAccount: mintBlocklistPubkey,
+              boostFrom: userRewardTokenAccount[1],
+              tokenProgram: TOKEN_PROGRAM_ID,
+            },
+          }
+        );
       },
       {
-        pubkey: rewardMintPubkey[1],
-        isWritable: true,
-        isSigner: false,
-      },
+        code: 6047,
+        // message: '6047: Expedition finished',
+      }
+    );
+  });
+
+  it("Unstake the Token-2022 NFT", async () => {
+    const userStakingCounterAccount =
+      await program.account.userStakingCounterAccount.fetch(
+        userStakingCounterPubkey
+      );
+    const thirdUserStakingIndex = userStakingCounterAccount.counter - 1;
+
+    let [thirdUserStakingPubkey, thirdUserStakingBump] =
+      await anchor.web3.PublicKey.findProgramAddress(
+        [
+          Buffer.from(
+            anchor.utils.bytes.utf8.encode(
+              new anchor.BN(thirdUserStakingIndex).toString()
+            )
+          ),
+          provider.wallet.publicKey.toBuffer(),
+        ],
+        program.programId
+      );
+
+    // the returned expedition's emission is claimed first
+    await program.rpc.claimAuryReward(
+      auryVaultBump,
+      thirdUserStakingIndex,
+      thirdUserStakingBump,
       {
-        pubkey: userRewardTokenAccount[1],
-        isWritable: true,
-        isSigner: false,
-      },
+        accounts: {
+          auryMint: auryMintPubkey,
+          auryVault: auryVaultPubkey,
+          auryTo: userAuryTokenAccount,
+          auryToAuthority: provider.wallet.publicKey,
+          stakingAccount: stakingPubkey,
+          userStakingAccount: thirdUserStakingPubkey,
+          mintBlocklistAccount: mintBlocklistPubkey,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        },
+      }
+    );
+
+    // Remaining accounts - mint(readonly), edition(readonly), tokenAccount(writable), vault(writable), aurorianStats(writable), stakeRecord(writable)
+    let remainingAccounts = [
       {
-        pubkey: rewardMintPubkey[2],
-        isWritable: true,
+        pubkey: nftMintPubkey[token2022Nft],
+        isWritable: false,
         isSigner: false,
       },
       {
-        pubkey: userRewardTokenAccount[2],
-        isWritable: true,
+        pubkey: nftEditionPubkey[token2022Nft],
+        isWritable: false,
         isSigner: false,
       },
       {
-        pubkey: rewardMintPubkey[3],
+        pubkey: userNFTTokenAccount[token2022Nft],
         isWritable: true,
         isSigner: false,
       },
       {
-        pubkey: userRewardTokenAccount[3],
+        pubkey: nftVaultPubkey[token2022Nft],
         isWritable: true,
         isSigner: false,
       },
       {
-        pubkey: rewardMintPubkey[4],
+        pubkey: nftStatsPubkey[token2022Nft],
         isWritable: true,
         isSigner: false,
       },
       {
-        pubkey: userRewardTokenAccount[4],
+        pubkey: nftStakeRecordPubkey[token2022Nft],
         isWritable: true,
         isSigner: false,
       },
     ];
 
-    await program.rpc.claim(
+    await program.rpc.unstake(
       stakingBump,
-      nextUserStakingIndex,
-      nextUserStakingBump,
+      thirdUserStakingIndex,
+      thirdUserStakingBump,
       {
         accounts: {
           nftToAuthority: provider.wallet.publicKey,
           stakingAccount: stakingPubkey,
-          userStakingAccount: nextUserStakingPubkey,
-          tokenProgram: TOKEN_PROGRAM_ID,
+          userStakingAccount: thirdUserStakingPubkey,
+          walletPositionsAccount: walletPositionsPubkey,
+          destinationAccount: destinationPubkey,
+          tokenMetadataProgram: METADATA_PROGRAM_ID,
+          systemProgram: anchor.web3.SystemProgram.programId,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
         },
         remainingAccounts,
       }
     );
-  });
 
-  it("Claim the aury reward", async () => {
-    await program.rpc.claimAuryReward(
-      auryVaultBump,
-      nextUserStakingIndex,
-      nextUserStakingBump,
+    assert.equal(await getTokenBalance(userNFTTokenAccount[token2022Nft]), 1);
+    await assert.rejects(
+      async () => {
+        await nftToken[token2022Nft].getAccountInfo(
+          nftVaultPubkey[token2022Nft]
+        );
+      },
+      {
+        message: "Failed to find account",
+      }
+    );
+    assert.equal(
+      await provider.connection.getAccountInfo(
+        nftStakeRecordPubkey[token2022Nft]
+      ),
+      null
+    );
+
+    // the unregistered Aurorian and the nft staked in place are still on the expedition
+    const thirdUserStakingAccount =
+      await program.account.userStakingAccount.fetch(thirdUserStakingPubkey);
+    assert.equal(
+      thirdUserStakingAccount.nftMintKeys.toString(),
+      [nftMintPubkey[nftCount - 1], nftMintPubkey[nonCustodialNft]].toString()
+    );
+
+    await program.rpc.removeAuthorizedCollections(
+      stakingBump,
+      [token2022NftCollection],
       {
         accounts: {
-          auryMint: auryMintPubkey,
-          auryVault: auryVaultPubkey,
-          auryTo: userAuryTokenAccount,
-          auryToAuthority: provider.wallet.publicKey,
-          userStakingAccount: nextUserStakingPubkey,
-          tokenProgram: TOKEN_PROGRAM_ID,
+          stakingAccount: stakingPubkey,
+          admin: provider.wallet.publicKey,
         },
       }
     );
   });
 
-  it("Unstake - maximum size is 5", async () => {
-    await sleep(1000);
+  it("Unstake the NFT staked non-custodially", async () => {
+    const userStakingCounterAccount =
+      await program.account.userStakingCounterAccount.fetch(
+        userStakingCounterPubkey
+      );
+    const thirdUserStakingIndex = userStakingCounterAccount.counter - 1;
+
+    let [thirdUserStakingPubkey, thirdUserStakingBump] =
+      await anchor.web3.PublicKey.findProgramAddress(
+        [
+          Buffer.from(
+            anchor.utils.bytes.utf8.encode(
+              new anchor.BN(thirdUserStakingIndex).toString()
+            )
+          ),
+          provider.wallet.publicKey.toBuffer(),
+        ],
+        program.programId
+      );
 
-    // Remaining accounts - mint(writable), vault(writable)
+    // Remaining accounts - mint(readonly), edition(readonly), tokenAccount(writable), vault(writable), aurorianStats(writable), stakeRecord(writable)
     let remainingAccounts = [
       {
-        pubkey: userNFTTokenAccount[0],
-        isWritable: true,
-        isSigner: false,
-      },
-      {
-        pubkey: nftVaultPubkey[0],
-        isWritable: true,
-        isSigner: false,
-      },
-      {
-        pubkey: userNFTTokenAccount[1],
-        isWritable: true,
-        isSigner: false,
-      },
-      {
-        pubkey: nftVaultPubkey[1],
-        isWritable: true,
-        isSigner: false,
-      },
-      {
-        pubkey: userNFTTokenAccount[2],
-        isWritable: true,
+        pubkey: nftMintPubkey[nonCustodialNft],
+        isWritable: false,
         isSigner: false,
       },
       {
-        pubkey: nftVaultPubkey[2],
-        isWritable: true,
+        pubkey: nftEditionPubkey[nonCustodialNft],
+        isWritable: false,
         isSigner: false,
       },
       {
-        pubkey: userNFTTokenAccount[3],
+        pubkey: userNFTTokenAccount[nonCustodialNft],
         isWritable: true,
         isSigner: false,
       },
       {
-        pubkey: nftVaultPubkey[3],
+        pubkey: userNFTTokenAccount[nonCustodialNft],
         isWritable: true,
         isSigner: false,
       },
       {
-        pubkey: userNFTTokenAccount[4],
+        pubkey: nftStatsPubkey[nonCustodialNft],
         isWritable: true,
         isSigner: false,
       },
       {
-        pubkey: nftVaultPubkey[4],
+        pubkey: nftStakeRecordPubkey[nonCustodialNft],
         isWritable: true,
         isSigner: false,
       },
@@ -2042,18 +5846,37 @@ describe("nft-staking", () => {
 
     await program.rpc.unstake(
       stakingBump,
-      nextUserStakingIndex,
-      nextUserStakingBump,
+      thirdUserStakingIndex,
+      thirdUserStakingBump,
       {
         accounts: {
           nftToAuthority: provider.wallet.publicKey,
           stakingAccount: stakingPubkey,
-          userStakingAccount: nextUserStakingPubkey,
+          userStakingAccount: thirdUserStakingPubkey,
+          walletPositionsAccount: walletPositionsPubkey,
+          destinationAccount: destinationPubkey,
+          tokenMetadataProgram: METADATA_PROGRAM_ID,
+          systemProgram: anchor.web3.SystemProgram.programId,
           tokenProgram: TOKEN_PROGRAM_ID,
         },
         remainingAccounts,
       }
     );
+
+    // the nft is thawed and the delegation revoked
+    const tokenAccount = await nftToken[nonCustodialNft].getAccountInfo(
+      userNFTTokenAccount[nonCustodialNft]
+    );
+    assert.equal(tokenAccount.amount.toNumber(), 1);
+    assert.ok(!tokenAccount.isFrozen);
+    assert.equal(tokenAccount.delegate, null);
+    assert.equal(tokenAccount.delegatedAmount.toNumber(), 0);
+    assert.equal(
+      await provider.connection.getAccountInfo(
+        nftStakeRecordPubkey[nonCustodialNft]
+      ),
+      null
+    );
   });
 
   it("RemoveReward - maximum size is 10", async () => {
@@ -2122,6 +5945,26 @@ describe("nft-staking", () => {
     });
   });
 
+  it("Mint to failed with mis-match token program", async () => {
+    await assert.rejects(
+      async () => {
+        await program.rpc.mintTo(stakingBump, new anchor.BN(1), {
+          accounts: {
+            nftMint: rewardMintPubkey[10],
+            nftTo: userRewardTokenAccount[10],
+            stakingAccount: stakingPubkey,
+            admin: provider.wallet.publicKey,
+            tokenProgram: TOKEN_2022_PROGRAM_ID,
+          },
+        });
+      },
+      {
+        code: 6040,
+        // message: '6040: Invalid token program',
+      }
+    );
+  });
+
   it("Mint to", async () => {
     // Old balance
     let oldBalance = await getTokenBalance(userRewardTokenAccount[10]);
@@ -2147,6 +5990,7 @@ describe("nft-staking", () => {
     await program.rpc.closeUserStaking(userStakingIndex, userStakingBump, {
       accounts: {
         nftToAuthority: provider.wallet.publicKey,
+        stakingAccount: stakingPubkey,
         userStakingAccount: userStakingPubkey,
         // systemProgram: anchor.web3.SystemProgram.programId,
         // tokenProgram: TOKEN_PROGRAM_ID,
@@ -2165,6 +6009,13 @@ describe("nft-staking", () => {
   });
 });
 
+// web3.js refuses to serialize a transaction over the packet size
+function isTransactionTooLarge(err: Error) {
+  return (
+    err instanceof RangeError || err.message.startsWith("Transaction too large")
+  );
+}
+
 async function getTokenBalance(pubkey: PublicKey) {
   return parseInt(
     (await provider.connection.getTokenAccountBalance(pubkey)).value.amount
@@ -7,10 +7,16 @@ import {
 } from "@solana/spl-token";
 import { Token } from "@solana/spl-token";
 import { TokenInstructions } from "@project-serum/serum";
-import { web3, Provider } from "@project-serum/anchor";
+import { web3, Provider, BN } from "@project-serum/anchor";
 
-const { Metadata, MetadataDataData, CreateMetadata, Creator } =
-  programs.metadata;
+const {
+  Metadata,
+  MetadataDataData,
+  CreateMetadata,
+  Creator,
+  MasterEdition,
+  CreateMasterEditionV3,
+} = programs.metadata;
 const Transaction = programs.Transaction;
 
 export function sleep(ms) {
@@ -100,6 +106,24 @@ export async function createMint(
   return [mint, metadataPDA, tx];
 }
 
+// Hands the mint and freeze authorities of a minted NFT to its master edition
+export async function createMasterEdition(provider: Provider, mint: PublicKey) {
+  const edition = await MasterEdition.getPDA(mint);
+  const tx = new CreateMasterEditionV3(
+    { feePayer: provider.wallet.publicKey },
+    {
+      edition,
+      metadata: await Metadata.getPDA(mint),
+      updateAuthority: provider.wallet.publicKey,
+      mint,
+      mintAuthority: provider.wallet.publicKey,
+      maxSupply: new BN(0),
+    }
+  );
+  await provider.send(tx);
+  return edition;
+}
+
 export async function setMintAuthority(
   provider: Provider,
   mint: PublicKey,
@@ -123,12 +147,13 @@ export async function mintToAccount(
   provider: Provider,
   mint: PublicKey,
   destination: PublicKey,
-  amount: number
+  amount: number,
+  programId: PublicKey = TOKEN_PROGRAM_ID
 ) {
   const tx = new Transaction();
   tx.add(
     Token.createMintToInstruction(
-      TOKEN_PROGRAM_ID,
+      programId,
       mint,
       destination,
       provider.wallet.publicKey,